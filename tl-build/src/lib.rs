@@ -0,0 +1,239 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+use thiserror::Error;
+use tl_generator::{Options, Role};
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("environment variable {0} is not set, tl-build runs in build scripts")]
+    MissingEnv(&'static str),
+
+    #[error("{path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    #[error("invalid tl.toml: {0}")]
+    Manifest(#[from] toml::de::Error),
+
+    #[error("{}: schema file names must be valid TL names, they name the generated modules", .path.display())]
+    InvalidSchemaName { path: PathBuf },
+
+    #[error("failed to parse schema: {name}: {count} errors")]
+    Schema {
+        name: String,
+        count: usize,
+    },
+
+    #[error("failed to generate schema: {name}: {source}")]
+    Generate {
+        name: String,
+        source: tl_generator::Error,
+    },
+}
+
+// Options read from `tl.toml` next to `Cargo.toml`, every key is optional:
+//
+//     schemas = "schemas"
+//     runtime = "tl_types"
+//     role = "client"
+//     functions = ["get_user*"]
+//
+//     [extern_modules]
+//     common = "crate::common"
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Manifest {
+    // Directory with the schemas, relative to the crate root.
+    schemas: Option<PathBuf>,
+    runtime: Option<String>,
+    role: Option<ManifestRole>,
+    functions: Option<Vec<String>>,
+    extern_modules: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ManifestRole {
+    Client,
+    Server,
+    Both,
+}
+
+// Generates a module for every `schemas/*.tl` into `$OUT_DIR/schemas`, with a
+// `mod.rs` declaring them:
+//
+//     include!(concat!(env!("OUT_DIR"), "/schemas/mod.rs"));
+pub fn build() -> Result<(), Error> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").ok_or(Error::MissingEnv("CARGO_MANIFEST_DIR"))?;
+    let out_dir = env::var_os("OUT_DIR").ok_or(Error::MissingEnv("OUT_DIR"))?;
+    build_in(Path::new(&manifest_dir), &Path::new(&out_dir).join("schemas"))
+}
+
+// The generator options set in `tl.toml` of the crate at `manifest_dir`, for
+// tools showing generated names.
+pub fn read_options(manifest_dir: &Path) -> Result<Options, Error> {
+    Ok(read_manifest(&manifest_dir.join("tl.toml"))?.options())
+}
+
+fn build_in(manifest_dir: &Path, out_dir: &Path) -> Result<(), Error> {
+    let manifest_path = manifest_dir.join("tl.toml");
+    // A missing file would make cargo run the build script on every build.
+    if manifest_path.exists() {
+        rerun_if_changed(&manifest_path);
+    }
+    let manifest = read_manifest(&manifest_path)?;
+
+    let schemas_dir = manifest_dir.join(manifest.schemas.as_deref().unwrap_or(Path::new("schemas")));
+    // Cargo scans directories, so added and removed schemas are picked up too.
+    rerun_if_changed(&schemas_dir);
+
+    let mut names = fs::read_dir(&schemas_dir)
+        .and_then(|entries| entries.collect::<Result<Vec<_>, _>>())
+        .map_err(|source| Error::Io { path: schemas_dir.clone(), source })?
+        .into_iter()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "tl"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_owned()))
+        .collect::<Vec<_>>();
+    names.sort();
+    if let Some(name) = names.iter().find(|name| !tl_parser::is_valid_name(name)) {
+        return Err(Error::InvalidSchemaName { path: schemas_dir.join(format!("{name}.tl")) });
+    }
+
+    let options = manifest.options();
+
+    fs::create_dir_all(out_dir).map_err(|source| Error::Io { path: out_dir.to_owned(), source })?;
+
+    let mut modules = String::new();
+    for name in &names {
+        let path = schemas_dir.join(format!("{name}.tl"));
+        let source = read(&path)?;
+        let (schema, errors) = tl_parser::parse_schema_recovering(&source);
+        if !errors.is_empty() {
+            let color = use_color(|name| env::var(name).ok());
+            for err in &errors {
+                eprintln!("{}", tl_parser::diagnostic::render(err, &source, &path.to_string_lossy(), color));
+            }
+            return Err(Error::Schema { name: name.clone(), count: errors.len() });
+        }
+
+        let out_file = out_dir.join(format!("{name}.rs"));
+        let code = tl_generator::generate_with_options(&schema, &options)
+            .map_err(|source| Error::Generate { name: name.clone(), source })?;
+        write(&out_file, &code)?;
+        let module = tl_generator::escape_ident(name);
+        modules.push_str(&format!("pub mod {module} {{\n    include!({:?});\n}}\n", out_file.to_string_lossy()));
+    }
+
+    write(&out_dir.join("mod.rs"), &modules)
+}
+
+impl Manifest {
+    fn options(self) -> Options {
+        let defaults = Options::default();
+        Options {
+            runtime: self.runtime.unwrap_or(defaults.runtime),
+            extern_modules: self.extern_modules,
+            functions: self.functions,
+            role: match self.role {
+                Some(ManifestRole::Client) => Role::Client,
+                Some(ManifestRole::Server) => Role::Server,
+                Some(ManifestRole::Both) | None => Role::Both,
+            },
+        }
+    }
+}
+
+// Cargo captures build script output, so stderr is never a terminal here and
+// color has to be asked for.
+fn use_color(var: impl Fn(&str) -> Option<String>) -> bool {
+    if var("NO_COLOR").is_some() {
+        return false;
+    }
+    var("CLICOLOR_FORCE").is_some_and(|value| value != "0") || var("CARGO_TERM_COLOR").as_deref() == Some("always")
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, Error> {
+    if !path.exists() {
+        return Ok(Manifest::default());
+    }
+
+    Ok(toml::from_str(&read(path)?)?)
+}
+
+fn rerun_if_changed(path: &Path) {
+    println!("cargo:rerun-if-changed={}", path.display());
+}
+
+fn read(path: &Path) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|source| Error::Io { path: path.to_owned(), source })
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Error> {
+    fs::write(path, contents).map_err(|source| Error::Io { path: path.to_owned(), source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build() {
+        let dir = env::temp_dir().join(format!("tl-build-{}", std::process::id()));
+        let out_dir = dir.join("out");
+        fs::create_dir_all(dir.join("protocol")).unwrap();
+        fs::write(dir.join("tl.toml"), "schemas = \"protocol\"\nrole = \"client\"\n").unwrap();
+        let users = "type User id:int64 = User\nfunc get_user id:int64 = User\n";
+        fs::write(dir.join("protocol/users.tl"), users).unwrap();
+        fs::write(dir.join("protocol/type.tl"), "type Kind = Kind\n").unwrap();
+        fs::write(dir.join("protocol/chats.tl"), "type Chat id:int64 = Chat\n").unwrap();
+        fs::write(dir.join("protocol/notes.txt"), "not a schema").unwrap();
+
+        build_in(&dir, &out_dir).unwrap();
+        let modules = fs::read_to_string(out_dir.join("mod.rs")).unwrap();
+        assert!(modules.starts_with("pub mod chats {"));
+        assert!(modules.contains("pub mod users {"));
+        assert!(modules.contains("pub mod r#type {"));
+        assert!(!modules.contains("notes"));
+        let users = fs::read_to_string(out_dir.join("users.rs")).unwrap();
+        assert!(users.contains("impl crate::Serialize for GetUser"));
+        assert!(!users.contains("impl crate::Deserialize for GetUser"));
+
+        fs::write(dir.join("protocol/chats.tl"), "type Chat id:int65 = Chat\n").unwrap();
+        assert!(matches!(build_in(&dir, &out_dir), Err(Error::Schema { name, count: 1 }) if name == "chats"));
+
+        fs::write(dir.join("protocol/chats.tl"), "type Chat id:int64 = Chat\n").unwrap();
+        fs::write(dir.join("tl.toml"), "schemas = \"protocol\"\nruntime = \"tl types\"\n").unwrap();
+        assert!(matches!(build_in(&dir, &out_dir), Err(Error::Generate { name, .. }) if name == "chats"));
+
+        fs::write(dir.join("protocol/user-settings.tl"), "type Settings = Settings\n").unwrap();
+        assert!(matches!(
+            build_in(&dir, &out_dir),
+            Err(Error::InvalidSchemaName { path }) if path.ends_with("user-settings.tl"),
+        ));
+        fs::remove_file(dir.join("protocol/user-settings.tl")).unwrap();
+
+        fs::write(dir.join("tl.toml"), "role = \"peer\"\n").unwrap();
+        assert!(matches!(build_in(&dir, &out_dir), Err(Error::Manifest(_))));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn color() {
+        let color = |vars: &[(&str, &str)]| use_color(|name| {
+            vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())
+        });
+
+        assert!(!color(&[]));
+        assert!(color(&[("CARGO_TERM_COLOR", "always")]));
+        assert!(!color(&[("CARGO_TERM_COLOR", "auto")]));
+        assert!(color(&[("CLICOLOR_FORCE", "1")]));
+        assert!(!color(&[("CLICOLOR_FORCE", "0")]));
+        assert!(!color(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]));
+    }
+}
@@ -0,0 +1,37 @@
+use crate::{USAGE, read_schema};
+use anyhow::bail;
+use std::process::ExitCode;
+use tl_parser::compat::{check_compatibility, Severity};
+
+// Exits with 1 if there are breaking changes, `--allow-retired` accepts
+// definitions removed and reserved.
+pub(crate) fn run(args: &[String]) -> anyhow::Result<ExitCode> {
+    let json = args.iter().any(|arg| arg == "--json");
+    let allowed = if args.iter().any(|arg| arg == "--allow-retired") {
+        Severity::Retired
+    } else {
+        Severity::Compatible
+    };
+    let paths = args.iter()
+        .filter(|arg| *arg != "--json" && *arg != "--allow-retired")
+        .collect::<Vec<_>>();
+    let [old, new] = paths.as_slice() else {
+        bail!("expected two schemas\n{USAGE}");
+    };
+
+    let report = check_compatibility(&read_schema(old)?, &read_schema(new)?);
+
+    if json {
+        println!("{}", report.to_json());
+    } else {
+        for change in &report.changes {
+            println!("{change}");
+        }
+    }
+
+    Ok(if report.severity() > allowed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
@@ -0,0 +1,44 @@
+use crate::{USAGE, read_schema};
+use anyhow::{Context, bail};
+use std::process::ExitCode;
+use tl_parser::lint::{Config, Lint, lint_schema};
+
+// Exits with 1 if there are warnings, `--allow <lint>` disables a lint.
+pub(crate) fn run(args: &[String]) -> anyhow::Result<ExitCode> {
+    let mut config = Config::default();
+    let mut path = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--allow" => {
+                let name = args.next().with_context(|| format!("expected a lint after --allow\n{USAGE}"))?;
+                let Some(lint) = Lint::from_name(name) else {
+                    let names = Lint::ALL.map(Lint::name).join(", ");
+                    bail!("unknown lint: {name}, expected one of: {names}");
+                };
+                config.disable(lint);
+            }
+            "--max-fields" => {
+                let value = args.next().with_context(|| format!("expected a number after --max-fields\n{USAGE}"))?;
+                config.max_fields = value.parse().with_context(|| format!("invalid --max-fields: {value}"))?;
+            }
+            _ if path.is_none() => path = Some(arg),
+            _ => bail!("expected one schema\n{USAGE}"),
+        }
+    }
+    let Some(path) = path else {
+        bail!("expected one schema\n{USAGE}");
+    };
+
+    let warnings = lint_schema(&read_schema(path)?, &config);
+    for warning in &warnings {
+        println!("{path}:{}: {} [{}]", warning.line, warning.message, warning.lint);
+    }
+
+    Ok(if warnings.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
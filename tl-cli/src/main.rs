@@ -0,0 +1,49 @@
+mod compat;
+mod lint;
+
+use anyhow::{Context, bail};
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+use std::{env, fs};
+
+const USAGE: &str = "\
+usage:
+    tl compat <old.tl> <new.tl> [--json] [--allow-retired]
+    tl lint <schema.tl> [--allow <lint>]... [--max-fields <n>]";
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("compat") => compat::run(&args[1..]),
+        Some("lint") => lint::run(&args[1..]),
+        _ => {
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read_schema(path: &str) -> anyhow::Result<tl_parser::Schema> {
+    let source = fs::read_to_string(path)
+        .with_context(|| format!("failed to read schema: {path}"))?;
+
+    let (schema, errors) = tl_parser::parse_schema_recovering(&source);
+    if !errors.is_empty() {
+        let color = io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none();
+        for err in &errors {
+            eprintln!("{}", tl_parser::diagnostic::render(err, &source, path, color));
+        }
+        bail!("failed to parse schema: {path}: {} errors", errors.len());
+    }
+
+    Ok(schema)
+}
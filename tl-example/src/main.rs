@@ -24,7 +24,8 @@ mod functions {
 }
 
 // Located on client side.
-fn call<F: Serialize + tl::Call>(func: &F) -> Result<Result<F::Return, tl::api::Error>, tl::deserialize::Error> {
+#[allow(dead_code)]
+fn call<F: tl::ClientCall>(func: &F) -> Result<Result<F::Return, tl::api::Error>, tl::deserialize::Error> {
     let request = func.to_bytes();
 
     let response = respond(&request)?;
@@ -33,6 +34,7 @@ fn call<F: Serialize + tl::Call>(func: &F) -> Result<Result<F::Return, tl::api::
 }
 
 // Located on server side.
+#[allow(dead_code)]
 fn respond(request: &[u8]) -> Result<Vec<u8>, tl::deserialize::Error> {
     let func = tl::api::Function::from_bytes(request)?;
 
@@ -72,4 +74,34 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn schema_description() -> Result<(), tl::deserialize::Error> {
+        use tl::meta::types::{Definition, Field, Schema};
+
+        let Schema::Schema { fingerprint, definitions } = Schema::from_bytes(tl::api::SCHEMA_DESCRIPTION)?;
+
+        assert_eq!(fingerprint, tl::api::SCHEMA_FINGERPRINT);
+        assert_eq!(definitions.len(), 7);
+        assert_eq!(definitions[5], Definition::FunctionDefinition {
+            id: 1904452899,
+            name: "get_users".to_owned(),
+            fields: vec![Field::Field {
+                name: "user_ids".to_owned(),
+                typ: "[int64]".to_owned(),
+                extension: false,
+            }],
+            ret: "[User]".to_owned(),
+        });
+        let Definition::FunctionDefinition { fields, .. } = &definitions[6] else {
+            panic!("send_message is not a function: {:?}", definitions[6]);
+        };
+        assert_eq!(
+            fields.iter().map(|Field::Field { name, extension, .. }| (name.as_str(), *extension)).collect::<Vec<_>>(),
+            vec![("user_id", false), ("text", false), ("photos", false), ("reply_to", true)],
+        );
+        assert!(tl::api::SCHEMA.contains("func get_users user_ids:[int64] = [User]\n"));
+
+        Ok(())
+    }
 }
@@ -1 +1 @@
-pub mod api;
+include!(concat!(env!("OUT_DIR"), "/schemas/mod.rs"));
@@ -0,0 +1,444 @@
+pub const SCHEMA: &str = "extern common Money\ngroup Owned owner:int64 type:string\nrecord Empty\nrecord Price amount:Money\nrecord Ticket id:int64 ..Owned\ntype Account id:int64 ..Owned = Account\ntype Shape#1a2b3c4d self:int32 self_:int32 ..Owned = Shape\ntype Circle fn:float ..Owned | color:int32? = Shape\ntype Square side:float | = Shape\ntype Nothing#00000000 = Void\n\nfunc get_shape ..Owned = Shape\nfunc get_price shape:Shape | currency:string? = Price\nfunc get_account ticket:Ticket = Account\n\nreserved Removed\nreserved 0x3b09d573\n";
+pub const SCHEMA_FINGERPRINT: [u8; 32] = [
+    99, 166, 6, 221, 33, 22, 109, 192, 191, 112, 128, 2, 200, 63, 133, 128, 107, 199, 6,
+    143, 151, 220, 28, 205, 39, 28, 243, 0, 196, 127, 45, 221,
+];
+pub const SCHEMA_DESCRIPTION: &[u8] = &[
+    134, 243, 27, 150, 32, 99, 166, 6, 221, 33, 22, 109, 192, 191, 112, 128, 2, 200, 63,
+    133, 128, 107, 199, 6, 143, 151, 220, 28, 205, 39, 28, 243, 0, 196, 127, 45, 221, 11,
+    47, 113, 242, 200, 5, 69, 109, 112, 116, 121, 0, 47, 113, 242, 200, 5, 80, 114, 105,
+    99, 101, 1, 28, 176, 45, 178, 6, 97, 109, 111, 117, 110, 116, 5, 77, 111, 110, 101,
+    121, 0, 47, 113, 242, 200, 6, 84, 105, 99, 107, 101, 116, 3, 28, 176, 45, 178, 2,
+    105, 100, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 5, 111, 119, 110, 101, 114,
+    5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 4, 116, 121, 112, 101, 6, 115, 116,
+    114, 105, 110, 103, 0, 255, 166, 119, 76, 91, 246, 118, 165, 0, 0, 0, 0, 7, 65, 99,
+    99, 111, 117, 110, 116, 3, 28, 176, 45, 178, 2, 105, 100, 5, 105, 110, 116, 54, 52,
+    0, 28, 176, 45, 178, 5, 111, 119, 110, 101, 114, 5, 105, 110, 116, 54, 52, 0, 28,
+    176, 45, 178, 4, 116, 121, 112, 101, 6, 115, 116, 114, 105, 110, 103, 0, 7, 65, 99,
+    99, 111, 117, 110, 116, 255, 166, 119, 76, 77, 60, 43, 26, 0, 0, 0, 0, 5, 83, 104,
+    97, 112, 101, 4, 28, 176, 45, 178, 4, 115, 101, 108, 102, 5, 105, 110, 116, 51, 50,
+    0, 28, 176, 45, 178, 5, 115, 101, 108, 102, 95, 5, 105, 110, 116, 51, 50, 0, 28, 176,
+    45, 178, 5, 111, 119, 110, 101, 114, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178,
+    4, 116, 121, 112, 101, 6, 115, 116, 114, 105, 110, 103, 0, 5, 83, 104, 97, 112, 101,
+    255, 166, 119, 76, 240, 140, 184, 221, 0, 0, 0, 0, 6, 67, 105, 114, 99, 108, 101, 4,
+    28, 176, 45, 178, 2, 102, 110, 5, 102, 108, 111, 97, 116, 0, 28, 176, 45, 178, 5,
+    111, 119, 110, 101, 114, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 4, 116, 121,
+    112, 101, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178, 5, 99, 111, 108, 111,
+    114, 6, 105, 110, 116, 51, 50, 63, 1, 5, 83, 104, 97, 112, 101, 255, 166, 119, 76,
+    159, 173, 58, 185, 0, 0, 0, 0, 6, 83, 113, 117, 97, 114, 101, 1, 28, 176, 45, 178, 4,
+    115, 105, 100, 101, 5, 102, 108, 111, 97, 116, 0, 5, 83, 104, 97, 112, 101, 255, 166,
+    119, 76, 0, 0, 0, 0, 0, 0, 0, 0, 7, 78, 111, 116, 104, 105, 110, 103, 0, 4, 86, 111,
+    105, 100, 206, 170, 42, 19, 81, 126, 77, 166, 0, 0, 0, 0, 9, 103, 101, 116, 95, 115,
+    104, 97, 112, 101, 2, 28, 176, 45, 178, 5, 111, 119, 110, 101, 114, 5, 105, 110, 116,
+    54, 52, 0, 28, 176, 45, 178, 4, 116, 121, 112, 101, 6, 115, 116, 114, 105, 110, 103,
+    0, 5, 83, 104, 97, 112, 101, 206, 170, 42, 19, 178, 110, 173, 29, 0, 0, 0, 0, 9, 103,
+    101, 116, 95, 112, 114, 105, 99, 101, 2, 28, 176, 45, 178, 5, 115, 104, 97, 112, 101,
+    5, 83, 104, 97, 112, 101, 0, 28, 176, 45, 178, 8, 99, 117, 114, 114, 101, 110, 99,
+    121, 7, 115, 116, 114, 105, 110, 103, 63, 1, 5, 80, 114, 105, 99, 101, 206, 170, 42,
+    19, 249, 36, 110, 23, 0, 0, 0, 0, 11, 103, 101, 116, 95, 97, 99, 99, 111, 117, 110,
+    116, 1, 28, 176, 45, 178, 6, 116, 105, 99, 107, 101, 116, 6, 84, 105, 99, 107, 101,
+    116, 0, 7, 65, 99, 99, 111, 117, 110, 116,
+];
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {}
+impl crate::Serialize for Error {
+    fn serialize(&self, _dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+        match *self {}
+    }
+}
+impl crate::Deserialize for Error {
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Err(
+            crate::deserialize::Error::UnexpectedDefinitionId(id),
+        )
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    GetShape(self::functions::GetShape),
+    GetPrice(self::functions::GetPrice),
+    GetAccount(self::functions::GetAccount),
+}
+impl crate::Deserialize for Function {
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Ok(
+            match id {
+                2790096465u32 => {
+                    Self::GetShape(self::functions::GetShape::deserialize(src)?)
+                }
+                497905330u32 => {
+                    Self::GetPrice(self::functions::GetPrice::deserialize(src)?)
+                }
+                393094393u32 => {
+                    Self::GetAccount(self::functions::GetAccount::deserialize(src)?)
+                }
+                _ => {
+                    return ::core::result::Result::Err(
+                        crate::deserialize::Error::UnexpectedDefinitionId(id),
+                    );
+                }
+            },
+        )
+    }
+}
+pub mod types {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Empty {}
+    impl crate::Serialize for Empty {
+        fn serialize(&self, _dst: &mut ::std::vec::Vec<::core::primitive::u8>) {}
+    }
+    impl crate::Deserialize for Empty {
+        fn deserialize(
+            _src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            ::core::result::Result::Ok(Self {})
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Price {
+        pub amount: crate::common::types::Money,
+    }
+    impl crate::Serialize for Price {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            self.amount.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for Price {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let amount_ = crate::common::types::Money::deserialize(src)?;
+            ::core::result::Result::Ok(Self { amount: amount_ })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Ticket {
+        pub id: ::core::primitive::i64,
+        pub owner: ::core::primitive::i64,
+        pub r#type: ::std::string::String,
+    }
+    impl crate::Serialize for Ticket {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            self.id.serialize(dst);
+            self.owner.serialize(dst);
+            self.r#type.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for Ticket {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id_ = ::core::primitive::i64::deserialize(src)?;
+            let owner_ = ::core::primitive::i64::deserialize(src)?;
+            let type_ = ::std::string::String::deserialize(src)?;
+            ::core::result::Result::Ok(Self {
+                id: id_,
+                owner: owner_,
+                r#type: type_,
+            })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Account {
+        Account {
+            id: ::core::primitive::i64,
+            owner: ::core::primitive::i64,
+            r#type: ::std::string::String,
+        },
+    }
+    impl crate::Serialize for Account {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Account { id: id_, owner: owner_, r#type: type_ } => {
+                    2776036955u32.serialize(dst);
+                    id_.serialize(dst);
+                    owner_.serialize(dst);
+                    type_.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Account {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    2776036955u32 => {
+                        let id_ = ::core::primitive::i64::deserialize(src)?;
+                        let owner_ = ::core::primitive::i64::deserialize(src)?;
+                        let type_ = ::std::string::String::deserialize(src)?;
+                        Self::Account {
+                            id: id_,
+                            owner: owner_,
+                            r#type: type_,
+                        }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Shape {
+        Shape {
+            self_: ::core::primitive::i32,
+            self__: ::core::primitive::i32,
+            owner: ::core::primitive::i64,
+            r#type: ::std::string::String,
+        },
+        Circle {
+            r#fn: ::core::primitive::f64,
+            owner: ::core::primitive::i64,
+            r#type: ::std::string::String,
+            color: ::core::option::Option<::core::primitive::i32>,
+        },
+        Square { side: ::core::primitive::f64 },
+    }
+    impl crate::Serialize for Shape {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Shape {
+                    self_: self__,
+                    self__: self___,
+                    owner: owner_,
+                    r#type: type_,
+                } => {
+                    439041101u32.serialize(dst);
+                    self__.serialize(dst);
+                    self___.serialize(dst);
+                    owner_.serialize(dst);
+                    type_.serialize(dst);
+                }
+                Self::Circle {
+                    r#fn: fn_,
+                    owner: owner_,
+                    r#type: type_,
+                    color: color_,
+                } => {
+                    3719859440u32.serialize(dst);
+                    fn_.serialize(dst);
+                    owner_.serialize(dst);
+                    type_.serialize(dst);
+                    let mut extensions = ::std::vec::Vec::<::core::primitive::u8>::new();
+                    color_.serialize(&mut extensions);
+                    extensions.serialize(dst);
+                }
+                Self::Square { side: side_ } => {
+                    3107630495u32.serialize(dst);
+                    side_.serialize(dst);
+                    ::std::vec::Vec::<::core::primitive::u8>::new().serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Shape {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    439041101u32 => {
+                        let self__ = ::core::primitive::i32::deserialize(src)?;
+                        let self___ = ::core::primitive::i32::deserialize(src)?;
+                        let owner_ = ::core::primitive::i64::deserialize(src)?;
+                        let type_ = ::std::string::String::deserialize(src)?;
+                        Self::Shape {
+                            self_: self__,
+                            self__: self___,
+                            owner: owner_,
+                            r#type: type_,
+                        }
+                    }
+                    3719859440u32 => {
+                        let fn_ = ::core::primitive::f64::deserialize(src)?;
+                        let owner_ = ::core::primitive::i64::deserialize(src)?;
+                        let type_ = ::std::string::String::deserialize(src)?;
+                        let extensions = ::std::vec::Vec::<
+                            ::core::primitive::u8,
+                        >::deserialize(src)?;
+                        let src = &mut extensions.as_slice();
+                        let color_ = crate::deserialize::extension::<
+                            ::core::option::Option<::core::primitive::i32>,
+                        >(src)?;
+                        Self::Circle {
+                            r#fn: fn_,
+                            owner: owner_,
+                            r#type: type_,
+                            color: color_,
+                        }
+                    }
+                    3107630495u32 => {
+                        let side_ = ::core::primitive::f64::deserialize(src)?;
+                        ::std::vec::Vec::<::core::primitive::u8>::deserialize(src)?;
+                        Self::Square { side: side_ }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Void {
+        Nothing {},
+    }
+    impl crate::Serialize for Void {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Nothing {} => {
+                    0u32.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Void {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    0u32 => Self::Nothing {},
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+}
+pub mod functions {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetShape {
+        pub owner: ::core::primitive::i64,
+        pub r#type: ::std::string::String,
+    }
+    impl crate::Serialize for GetShape {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            2790096465u32.serialize(dst);
+            self.owner.serialize(dst);
+            self.r#type.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for GetShape {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let owner_ = ::core::primitive::i64::deserialize(src)?;
+            let type_ = ::std::string::String::deserialize(src)?;
+            ::core::result::Result::Ok(Self {
+                owner: owner_,
+                r#type: type_,
+            })
+        }
+    }
+    impl crate::Call for GetShape {
+        type Return = super::types::Shape;
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetPrice {
+        pub shape: super::types::Shape,
+        pub currency: ::core::option::Option<::std::string::String>,
+    }
+    impl crate::Serialize for GetPrice {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            497905330u32.serialize(dst);
+            self.shape.serialize(dst);
+            let mut extensions = ::std::vec::Vec::<::core::primitive::u8>::new();
+            self.currency.serialize(&mut extensions);
+            extensions.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for GetPrice {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let shape_ = super::types::Shape::deserialize(src)?;
+            let extensions = ::std::vec::Vec::<::core::primitive::u8>::deserialize(src)?;
+            let src = &mut extensions.as_slice();
+            let currency_ = crate::deserialize::extension::<
+                ::core::option::Option<::std::string::String>,
+            >(src)?;
+            ::core::result::Result::Ok(Self {
+                shape: shape_,
+                currency: currency_,
+            })
+        }
+    }
+    impl crate::Call for GetPrice {
+        type Return = super::types::Price;
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetAccount {
+        pub ticket: super::types::Ticket,
+    }
+    impl crate::Serialize for GetAccount {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            393094393u32.serialize(dst);
+            self.ticket.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for GetAccount {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let ticket_ = super::types::Ticket::deserialize(src)?;
+            ::core::result::Result::Ok(Self { ticket: ticket_ })
+        }
+    }
+    impl crate::Call for GetAccount {
+        type Return = super::types::Account;
+    }
+}
+pub mod groups {
+    pub trait Owned {
+        fn owner(&self) -> &::core::primitive::i64;
+        fn r#type(&self) -> &::std::string::String;
+    }
+    impl Owned for super::types::Account {
+        fn owner(&self) -> &::core::primitive::i64 {
+            match self {
+                Self::Account { owner, .. } => owner,
+            }
+        }
+        fn r#type(&self) -> &::std::string::String {
+            match self {
+                Self::Account { r#type, .. } => r#type,
+            }
+        }
+    }
+    impl Owned for super::types::Ticket {
+        fn owner(&self) -> &::core::primitive::i64 {
+            &self.owner
+        }
+        fn r#type(&self) -> &::std::string::String {
+            &self.r#type
+        }
+    }
+    impl Owned for super::functions::GetShape {
+        fn owner(&self) -> &::core::primitive::i64 {
+            &self.owner
+        }
+        fn r#type(&self) -> &::std::string::String {
+            &self.r#type
+        }
+    }
+}
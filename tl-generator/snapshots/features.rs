@@ -0,0 +1,254 @@
+pub const SCHEMA: &str = "@feature(\"legacy\") record Old value:int32\ntype Photo data:bytes = Photo\n@feature(\"legacy\") type LegacyPhoto old:Old = Photo\n@feature(\"legacy\") type Avatar url:string = Avatar\n\n@feature(\"legacy\") error Deprecated\n\nfunc upload_photo photo:Photo = Photo\n@feature(\"legacy\") func upload_avatar avatar:Avatar = void\n";
+pub const SCHEMA_FINGERPRINT: [u8; 32] = [
+    155, 244, 72, 94, 174, 117, 216, 222, 240, 75, 139, 64, 8, 42, 117, 56, 193, 233, 9,
+    7, 136, 231, 251, 188, 21, 189, 15, 132, 24, 3, 199, 20,
+];
+pub const SCHEMA_DESCRIPTION: &[u8] = &[
+    134, 243, 27, 150, 32, 155, 244, 72, 94, 174, 117, 216, 222, 240, 75, 139, 64, 8, 42,
+    117, 56, 193, 233, 9, 7, 136, 231, 251, 188, 21, 189, 15, 132, 24, 3, 199, 20, 7, 47,
+    113, 242, 200, 3, 79, 108, 100, 1, 28, 176, 45, 178, 5, 118, 97, 108, 117, 101, 5,
+    105, 110, 116, 51, 50, 0, 255, 166, 119, 76, 141, 253, 203, 206, 0, 0, 0, 0, 5, 80,
+    104, 111, 116, 111, 1, 28, 176, 45, 178, 4, 100, 97, 116, 97, 5, 98, 121, 116, 101,
+    115, 0, 5, 80, 104, 111, 116, 111, 255, 166, 119, 76, 108, 159, 67, 71, 0, 0, 0, 0,
+    11, 76, 101, 103, 97, 99, 121, 80, 104, 111, 116, 111, 1, 28, 176, 45, 178, 3, 111,
+    108, 100, 3, 79, 108, 100, 0, 5, 80, 104, 111, 116, 111, 255, 166, 119, 76, 28, 173,
+    59, 53, 0, 0, 0, 0, 6, 65, 118, 97, 116, 97, 114, 1, 28, 176, 45, 178, 3, 117, 114,
+    108, 6, 115, 116, 114, 105, 110, 103, 0, 6, 65, 118, 97, 116, 97, 114, 123, 219, 189,
+    67, 187, 79, 25, 137, 0, 0, 0, 0, 10, 68, 101, 112, 114, 101, 99, 97, 116, 101, 100,
+    0, 206, 170, 42, 19, 96, 227, 57, 180, 0, 0, 0, 0, 12, 117, 112, 108, 111, 97, 100,
+    95, 112, 104, 111, 116, 111, 1, 28, 176, 45, 178, 5, 112, 104, 111, 116, 111, 5, 80,
+    104, 111, 116, 111, 0, 5, 80, 104, 111, 116, 111, 206, 170, 42, 19, 249, 203, 31, 43,
+    0, 0, 0, 0, 13, 117, 112, 108, 111, 97, 100, 95, 97, 118, 97, 116, 97, 114, 1, 28,
+    176, 45, 178, 6, 97, 118, 97, 116, 97, 114, 6, 65, 118, 97, 116, 97, 114, 0, 4, 118,
+    111, 105, 100,
+];
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    #[cfg(feature = "legacy")]
+    Deprecated {},
+}
+impl crate::Serialize for Error {
+    #[allow(unused_variables)]
+    fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+        match self {
+            #[cfg(feature = "legacy")]
+            Self::Deprecated {} => {
+                2300137403u32.serialize(dst);
+            }
+            #[allow(unreachable_patterns)]
+            _ => unreachable!(),
+        };
+    }
+}
+impl crate::Deserialize for Error {
+    #[allow(unreachable_code)]
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Ok(
+            match id {
+                #[cfg(feature = "legacy")]
+                2300137403u32 => Self::Deprecated {},
+                _ => {
+                    return ::core::result::Result::Err(
+                        crate::deserialize::Error::UnexpectedDefinitionId(id),
+                    );
+                }
+            },
+        )
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    UploadPhoto(self::functions::UploadPhoto),
+    #[cfg(feature = "legacy")]
+    UploadAvatar(self::functions::UploadAvatar),
+}
+impl crate::Deserialize for Function {
+    #[allow(unreachable_code)]
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Ok(
+            match id {
+                3023692640u32 => {
+                    Self::UploadPhoto(self::functions::UploadPhoto::deserialize(src)?)
+                }
+                #[cfg(feature = "legacy")]
+                723504121u32 => {
+                    Self::UploadAvatar(self::functions::UploadAvatar::deserialize(src)?)
+                }
+                _ => {
+                    return ::core::result::Result::Err(
+                        crate::deserialize::Error::UnexpectedDefinitionId(id),
+                    );
+                }
+            },
+        )
+    }
+}
+pub mod types {
+    #[cfg(feature = "legacy")]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Old {
+        pub value: ::core::primitive::i32,
+    }
+    #[cfg(feature = "legacy")]
+    impl crate::Serialize for Old {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            self.value.serialize(dst);
+        }
+    }
+    #[cfg(feature = "legacy")]
+    impl crate::Deserialize for Old {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let value_ = ::core::primitive::i32::deserialize(src)?;
+            ::core::result::Result::Ok(Self { value: value_ })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Photo {
+        Photo { data: ::std::vec::Vec<::core::primitive::u8> },
+        #[cfg(feature = "legacy")]
+        LegacyPhoto { old: super::types::Old },
+    }
+    impl crate::Serialize for Photo {
+        #[allow(unused_variables)]
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Photo { data: data_ } => {
+                    3469475213u32.serialize(dst);
+                    data_.serialize(dst);
+                }
+                #[cfg(feature = "legacy")]
+                Self::LegacyPhoto { old: old_ } => {
+                    1195614060u32.serialize(dst);
+                    old_.serialize(dst);
+                }
+                #[allow(unreachable_patterns)]
+                _ => unreachable!(),
+            };
+        }
+    }
+    impl crate::Deserialize for Photo {
+        #[allow(unreachable_code)]
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    3469475213u32 => {
+                        let data_ = ::std::vec::Vec::<
+                            ::core::primitive::u8,
+                        >::deserialize(src)?;
+                        Self::Photo { data: data_ }
+                    }
+                    #[cfg(feature = "legacy")]
+                    1195614060u32 => {
+                        let old_ = super::types::Old::deserialize(src)?;
+                        Self::LegacyPhoto { old: old_ }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Avatar {
+        #[cfg(feature = "legacy")]
+        Avatar { url: ::std::string::String },
+    }
+    impl crate::Serialize for Avatar {
+        #[allow(unused_variables)]
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                #[cfg(feature = "legacy")]
+                Self::Avatar { url: url_ } => {
+                    893103388u32.serialize(dst);
+                    url_.serialize(dst);
+                }
+                #[allow(unreachable_patterns)]
+                _ => unreachable!(),
+            };
+        }
+    }
+    impl crate::Deserialize for Avatar {
+        #[allow(unreachable_code)]
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    #[cfg(feature = "legacy")]
+                    893103388u32 => {
+                        let url_ = ::std::string::String::deserialize(src)?;
+                        Self::Avatar { url: url_ }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+}
+pub mod functions {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct UploadPhoto {
+        pub photo: super::types::Photo,
+    }
+    impl crate::Serialize for UploadPhoto {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            3023692640u32.serialize(dst);
+            self.photo.serialize(dst);
+        }
+    }
+    impl crate::Deserialize for UploadPhoto {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let photo_ = super::types::Photo::deserialize(src)?;
+            ::core::result::Result::Ok(Self { photo: photo_ })
+        }
+    }
+    impl crate::Call for UploadPhoto {
+        type Return = super::types::Photo;
+    }
+    #[cfg(feature = "legacy")]
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct UploadAvatar {
+        pub avatar: super::types::Avatar,
+    }
+    #[cfg(feature = "legacy")]
+    impl crate::Serialize for UploadAvatar {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            723504121u32.serialize(dst);
+            self.avatar.serialize(dst);
+        }
+    }
+    #[cfg(feature = "legacy")]
+    impl crate::Deserialize for UploadAvatar {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let avatar_ = super::types::Avatar::deserialize(src)?;
+            ::core::result::Result::Ok(Self { avatar: avatar_ })
+        }
+    }
+    #[cfg(feature = "legacy")]
+    impl crate::Call for UploadAvatar {
+        type Return = ();
+    }
+}
@@ -0,0 +1,261 @@
+pub const SCHEMA: &str = "const MAX_COUNT:int32 = 100\nconst MIN_OFFSET:int64 = -9223372036854775808\nconst RATIO:float = -0.5\nconst ENABLED:bool = true\nconst GREETING:string = \"hello #1\"\n\nrecord Point x:float y:float\ntype Values int:int32 long:int64 float:float bool:bool string:string bytes:bytes time:time = Values\ntype Nested points:[Point] optional:Point? matrix:[[int32]] = Nested\ntype Leaf value:int32 = Tree\ntype Node children:[Tree] = Tree\n\nerror NotFound id:int64\nerror Internal\nerror TooMany count:int32 max_count:int32=MAX_COUNT\n\nfunc get_values = Values\nfunc get_tree root:Tree = Tree\nfunc get_points ids:[int64] limit:int32?=MAX_COUNT = [Point]\nfunc clear = void\n";
+pub const SCHEMA_FINGERPRINT: [u8; 32] = [
+    217, 123, 180, 138, 52, 135, 203, 113, 72, 34, 103, 69, 23, 254, 127, 216, 55, 54,
+    123, 118, 4, 135, 8, 157, 176, 185, 208, 19, 211, 199, 107, 183,
+];
+pub const SCHEMA_DESCRIPTION: &[u8] = &[
+    134, 243, 27, 150, 32, 217, 123, 180, 138, 52, 135, 203, 113, 72, 34, 103, 69, 23,
+    254, 127, 216, 55, 54, 123, 118, 4, 135, 8, 157, 176, 185, 208, 19, 211, 199, 107,
+    183, 12, 47, 113, 242, 200, 5, 80, 111, 105, 110, 116, 2, 28, 176, 45, 178, 1, 120,
+    5, 102, 108, 111, 97, 116, 0, 28, 176, 45, 178, 1, 121, 5, 102, 108, 111, 97, 116, 0,
+    255, 166, 119, 76, 20, 255, 127, 195, 0, 0, 0, 0, 6, 86, 97, 108, 117, 101, 115, 7,
+    28, 176, 45, 178, 3, 105, 110, 116, 5, 105, 110, 116, 51, 50, 0, 28, 176, 45, 178, 4,
+    108, 111, 110, 103, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 5, 102, 108, 111,
+    97, 116, 5, 102, 108, 111, 97, 116, 0, 28, 176, 45, 178, 4, 98, 111, 111, 108, 4, 98,
+    111, 111, 108, 0, 28, 176, 45, 178, 6, 115, 116, 114, 105, 110, 103, 6, 115, 116,
+    114, 105, 110, 103, 0, 28, 176, 45, 178, 5, 98, 121, 116, 101, 115, 5, 98, 121, 116,
+    101, 115, 0, 28, 176, 45, 178, 4, 116, 105, 109, 101, 4, 116, 105, 109, 101, 0, 6,
+    86, 97, 108, 117, 101, 115, 255, 166, 119, 76, 160, 24, 248, 160, 0, 0, 0, 0, 6, 78,
+    101, 115, 116, 101, 100, 3, 28, 176, 45, 178, 6, 112, 111, 105, 110, 116, 115, 7, 91,
+    80, 111, 105, 110, 116, 93, 0, 28, 176, 45, 178, 8, 111, 112, 116, 105, 111, 110, 97,
+    108, 6, 80, 111, 105, 110, 116, 63, 0, 28, 176, 45, 178, 6, 109, 97, 116, 114, 105,
+    120, 9, 91, 91, 105, 110, 116, 51, 50, 93, 93, 0, 6, 78, 101, 115, 116, 101, 100,
+    255, 166, 119, 76, 18, 165, 251, 10, 0, 0, 0, 0, 4, 76, 101, 97, 102, 1, 28, 176, 45,
+    178, 5, 118, 97, 108, 117, 101, 5, 105, 110, 116, 51, 50, 0, 4, 84, 114, 101, 101,
+    255, 166, 119, 76, 62, 16, 25, 86, 0, 0, 0, 0, 4, 78, 111, 100, 101, 1, 28, 176, 45,
+    178, 8, 99, 104, 105, 108, 100, 114, 101, 110, 6, 91, 84, 114, 101, 101, 93, 0, 4,
+    84, 114, 101, 101, 123, 219, 189, 67, 115, 235, 210, 141, 0, 0, 0, 0, 8, 78, 111,
+    116, 70, 111, 117, 110, 100, 1, 28, 176, 45, 178, 2, 105, 100, 5, 105, 110, 116, 54,
+    52, 0, 123, 219, 189, 67, 196, 28, 58, 224, 0, 0, 0, 0, 8, 73, 110, 116, 101, 114,
+    110, 97, 108, 0, 123, 219, 189, 67, 60, 199, 195, 131, 0, 0, 0, 0, 7, 84, 111, 111,
+    77, 97, 110, 121, 2, 28, 176, 45, 178, 5, 99, 111, 117, 110, 116, 5, 105, 110, 116,
+    51, 50, 0, 28, 176, 45, 178, 9, 109, 97, 120, 95, 99, 111, 117, 110, 116, 5, 105,
+    110, 116, 51, 50, 0, 206, 170, 42, 19, 89, 33, 178, 81, 0, 0, 0, 0, 10, 103, 101,
+    116, 95, 118, 97, 108, 117, 101, 115, 0, 6, 86, 97, 108, 117, 101, 115, 206, 170, 42,
+    19, 63, 104, 240, 3, 0, 0, 0, 0, 8, 103, 101, 116, 95, 116, 114, 101, 101, 1, 28,
+    176, 45, 178, 4, 114, 111, 111, 116, 4, 84, 114, 101, 101, 0, 4, 84, 114, 101, 101,
+    206, 170, 42, 19, 59, 246, 37, 17, 0, 0, 0, 0, 10, 103, 101, 116, 95, 112, 111, 105,
+    110, 116, 115, 2, 28, 176, 45, 178, 3, 105, 100, 115, 7, 91, 105, 110, 116, 54, 52,
+    93, 0, 28, 176, 45, 178, 5, 108, 105, 109, 105, 116, 6, 105, 110, 116, 51, 50, 63, 0,
+    7, 91, 80, 111, 105, 110, 116, 93, 206, 170, 42, 19, 66, 233, 96, 162, 0, 0, 0, 0, 5,
+    99, 108, 101, 97, 114, 0, 4, 118, 111, 105, 100,
+];
+pub const MAX_COUNT: ::core::primitive::i32 = 100;
+pub const MIN_OFFSET: ::core::primitive::i64 = -9223372036854775808;
+pub const RATIO: ::core::primitive::f64 = -0.5;
+pub const ENABLED: ::core::primitive::bool = true;
+pub const GREETING: &::core::primitive::str = "hello #1";
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    NotFound { id: ::core::primitive::i64 },
+    Internal {},
+    TooMany {
+        count: ::core::primitive::i32,
+        /// Usually `MAX_COUNT`.
+        max_count: ::core::primitive::i32,
+    },
+}
+impl crate::Deserialize for Error {
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Ok(
+            match id {
+                2379410291u32 => {
+                    let id_ = ::core::primitive::i64::deserialize(src)?;
+                    Self::NotFound { id: id_ }
+                }
+                3761904836u32 => Self::Internal {},
+                2210645820u32 => {
+                    let count_ = ::core::primitive::i32::deserialize(src)?;
+                    let max_count_ = ::core::primitive::i32::deserialize(src)?;
+                    Self::TooMany {
+                        count: count_,
+                        max_count: max_count_,
+                    }
+                }
+                _ => {
+                    return ::core::result::Result::Err(
+                        crate::deserialize::Error::UnexpectedDefinitionId(id),
+                    );
+                }
+            },
+        )
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {
+    GetValues(self::functions::GetValues),
+    GetTree(self::functions::GetTree),
+    GetPoints(self::functions::GetPoints),
+    Clear(self::functions::Clear),
+}
+pub mod types {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Point {
+        pub x: ::core::primitive::f64,
+        pub y: ::core::primitive::f64,
+    }
+    impl crate::Deserialize for Point {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let x_ = ::core::primitive::f64::deserialize(src)?;
+            let y_ = ::core::primitive::f64::deserialize(src)?;
+            ::core::result::Result::Ok(Self { x: x_, y: y_ })
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Values {
+        Values {
+            int: ::core::primitive::i32,
+            long: ::core::primitive::i64,
+            float: ::core::primitive::f64,
+            bool: ::core::primitive::bool,
+            string: ::std::string::String,
+            bytes: ::std::vec::Vec<::core::primitive::u8>,
+            time: ::std::time::SystemTime,
+        },
+    }
+    impl crate::Deserialize for Values {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    3279945492u32 => {
+                        let int_ = ::core::primitive::i32::deserialize(src)?;
+                        let long_ = ::core::primitive::i64::deserialize(src)?;
+                        let float_ = ::core::primitive::f64::deserialize(src)?;
+                        let bool_ = ::core::primitive::bool::deserialize(src)?;
+                        let string_ = ::std::string::String::deserialize(src)?;
+                        let bytes_ = ::std::vec::Vec::<
+                            ::core::primitive::u8,
+                        >::deserialize(src)?;
+                        let time_ = ::std::time::SystemTime::deserialize(src)?;
+                        Self::Values {
+                            int: int_,
+                            long: long_,
+                            float: float_,
+                            bool: bool_,
+                            string: string_,
+                            bytes: bytes_,
+                            time: time_,
+                        }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Nested {
+        Nested {
+            points: ::std::vec::Vec<super::types::Point>,
+            optional: ::core::option::Option<super::types::Point>,
+            matrix: ::std::vec::Vec<::std::vec::Vec<::core::primitive::i32>>,
+        },
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Tree {
+        Leaf { value: ::core::primitive::i32 },
+        Node { children: ::std::vec::Vec<super::types::Tree> },
+    }
+    impl crate::Serialize for Tree {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Leaf { value: value_ } => {
+                    184263954u32.serialize(dst);
+                    value_.serialize(dst);
+                }
+                Self::Node { children: children_ } => {
+                    1444483134u32.serialize(dst);
+                    children_.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Tree {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    184263954u32 => {
+                        let value_ = ::core::primitive::i32::deserialize(src)?;
+                        Self::Leaf { value: value_ }
+                    }
+                    1444483134u32 => {
+                        let children_ = ::std::vec::Vec::<
+                            super::types::Tree,
+                        >::deserialize(src)?;
+                        Self::Node { children: children_ }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+}
+pub mod functions {
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetValues {}
+    impl crate::Serialize for GetValues {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            1370628441u32.serialize(dst);
+        }
+    }
+    impl crate::Call for GetValues {
+        type Return = super::types::Values;
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetTree {
+        pub root: super::types::Tree,
+    }
+    impl crate::Serialize for GetTree {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            66086975u32.serialize(dst);
+            self.root.serialize(dst);
+        }
+    }
+    impl crate::Call for GetTree {
+        type Return = super::types::Tree;
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GetPoints {
+        pub ids: ::std::vec::Vec<::core::primitive::i64>,
+        /// Usually `MAX_COUNT`.
+        pub limit: ::core::option::Option<::core::primitive::i32>,
+    }
+    impl crate::Serialize for GetPoints {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            287700539u32.serialize(dst);
+            self.ids.serialize(dst);
+            self.limit.serialize(dst);
+        }
+    }
+    impl crate::Call for GetPoints {
+        type Return = ::std::vec::Vec<super::types::Point>;
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Clear {}
+    impl crate::Serialize for Clear {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            2724260162u32.serialize(dst);
+        }
+    }
+    impl crate::Call for Clear {
+        type Return = ();
+    }
+}
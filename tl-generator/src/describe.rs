@@ -0,0 +1,47 @@
+use tl_parser::{DefinitionCore, Schema};
+use tl_types::meta::types::{Definition, Field};
+use tl_types::Serialize;
+
+// Encodes the schema as a `Schema` of the built-in meta-schema.
+pub(crate) fn describe_schema(schema: &Schema) -> Vec<u8> {
+    let records = schema.records.iter()
+        .map(|def| Definition::RecordDefinition {
+            name: def.core.name.clone(),
+            fields: fields(&def.core),
+        });
+    let types = schema.types.iter()
+        .map(|def| Definition::TypeDefinition {
+            id: def.core.id.into(),
+            name: def.core.name.clone(),
+            fields: fields(&def.core),
+            enum_name: def.r#enum.clone(),
+        });
+    let errors = schema.errors.iter()
+        .map(|def| Definition::ErrorDefinition {
+            id: def.core.id.into(),
+            name: def.core.name.clone(),
+            fields: fields(&def.core),
+        });
+    let functions = schema.functions.iter()
+        .map(|def| Definition::FunctionDefinition {
+            id: def.core.id.into(),
+            name: def.core.name.clone(),
+            fields: fields(&def.core),
+            ret: def.ret.to_string(),
+        });
+
+    tl_types::meta::types::Schema::Schema {
+        fingerprint: schema.fingerprint().to_vec(),
+        definitions: records.chain(types).chain(errors).chain(functions).collect(),
+    }.to_bytes()
+}
+
+fn fields(core: &DefinitionCore) -> Vec<Field> {
+    core.fields.iter()
+        .map(|field| Field::Field {
+            name: field.name.clone(),
+            typ: field.typ.to_string(),
+            extension: field.extension,
+        })
+        .collect()
+}
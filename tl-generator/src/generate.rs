@@ -1,364 +1,670 @@
-use crate::Output;
+use crate::describe::describe_schema;
+use crate::ident::{escape_ident, ident};
+use crate::Role;
 use convert_case::{Case, Casing};
-use std::collections::HashMap;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{format_ident, quote};
+use std::collections::{HashMap, HashSet};
+use syn::ext::IdentExt;
+use syn::Path;
 use tl_parser::*;
 
+pub(crate) struct Context<'a> {
+    pub(crate) runtime: Path,
+    // Modules of extern types, by type name.
+    pub(crate) externs: HashMap<&'a str, Path>,
+    pub(crate) role: Role,
+    // Types sent in function fields and in results or errors, with the types
+    // they contain.
+    pub(crate) requests: HashSet<&'a str>,
+    pub(crate) responses: HashSet<&'a str>,
+}
+
+impl Context<'_> {
+    fn impls(&self, request: bool, response: bool) -> Impls {
+        let (writes, reads) = match self.role {
+            Role::Client => (request, response),
+            Role::Server => (response, request),
+            Role::Both => (true, true),
+        };
+        Impls { serialize: writes, deserialize: reads }
+    }
+
+    fn type_impls(&self, name: &str) -> Impls {
+        self.impls(self.requests.contains(name), self.responses.contains(name))
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Impls {
+    serialize: bool,
+    deserialize: bool,
+}
+
 pub(crate) fn generate_schema(
-    o: &mut Output,
+    cx: &Context,
     schema: &Schema,
-) {
-    generate_enum(
-        o, "Error", false,
+) -> TokenStream {
+    let constants = schema.constants.iter()
+        .map(|constant| generate_constant(cx, constant));
+
+    let errors = generate_enum(
+        cx, &ident("Error"), false, false, cx.impls(false, true),
         &schema.errors.iter()
             .map(|def| &def.core)
             .collect::<Vec<_>>(),
     );
 
-    o.write("\n");
-
-    generate_enum(
-        o, "Function", true,
+    let functions = generate_enum(
+        cx, &ident("Function"), true, false, cx.impls(true, false),
         &schema.functions.iter()
             .map(|def| &def.core)
             .collect::<Vec<_>>(),
     );
 
-    o.write("\n");
+    let records = schema.records.iter()
+        .map(|def| generate_definition(cx, &def.core, None, cx.type_impls(&def.core.name)));
 
-    o.write_line(|o| o.write("pub mod types {"));
-    o.with_indent(|o| {
-        let mut enums = HashMap::new();
-        for def in &schema.types {
-            enums.entry(def.r#enum.clone()).or_insert_with(Vec::new).push(&def.core);
-        }
-        for (name, definitions) in enums {
-            generate_enum(o, &name, false, &definitions);
-            o.write("\n");
+    let enums = enums(schema).into_iter()
+        .map(|(name, definitions)| {
+            generate_enum(cx, &ident(name), false, true, cx.type_impls(name), &definitions)
+        });
+
+    let definitions = schema.functions.iter()
+        .map(|def| generate_definition(cx, &def.core, Some(&def.ret), cx.impls(true, false)));
+
+    let groups = (!schema.groups.is_empty()).then(|| {
+        let groups = schema.groups.iter().map(|group| generate_group(cx, schema, group));
+        quote! {
+            pub mod groups {
+                #(#groups)*
+            }
         }
     });
-    o.write_line(|o| o.write("}"));
 
-    o.write("\n");
+    quote! {
+        #(#constants)*
+
+        #errors
 
-    o.write_line(|o| o.write("pub mod functions {"));
-    o.with_indent(|o| {
-        for def in &schema.functions {
-            generate_definition(o, &def.core, Some(&def.ret));
-            o.write("\n");
+        #functions
+
+        pub mod types {
+            #(#records)*
+            #(#enums)*
         }
-    });
-    o.write_line(|o| o.write("}"));
+
+        pub mod functions {
+            #(#definitions)*
+        }
+
+        #groups
+    }
 }
 
-fn get_definition_name(
-    def: &DefinitionCore,
+// Type enums with their constructors, in declaration order so the output
+// is the same on every build.
+fn enums(schema: &Schema) -> Vec<(&str, Vec<&DefinitionCore>)> {
+    let mut enums: Vec<(&str, Vec<&DefinitionCore>)> = Vec::new();
+    for def in &schema.types {
+        match enums.iter_mut().find(|(name, _)| *name == def.r#enum) {
+            Some((_, definitions)) => definitions.push(&def.core),
+            None => enums.push((&def.r#enum, vec![&def.core])),
+        }
+    }
+    enums
+}
+
+pub(crate) fn generate_description(schema: &Schema) -> TokenStream {
+    let canonical = schema.canonical();
+    let fingerprint = schema.fingerprint().map(Literal::u8_unsuffixed);
+    let description = describe_schema(schema).into_iter().map(Literal::u8_unsuffixed);
+
+    quote! {
+        pub const SCHEMA: &str = #canonical;
+        pub const SCHEMA_FINGERPRINT: [u8; 32] = [#(#fingerprint),*];
+        pub const SCHEMA_DESCRIPTION: &[u8] = &[#(#description),*];
+    }
+}
+
+fn generate_constant(
+    cx: &Context,
+    constant: &Constant,
+) -> TokenStream {
+    let name = ident(&constant.name);
+    let typ = match constant.typ {
+        Type::String => quote!(&::core::primitive::str),
+        _ => generate_type(cx, &constant.typ, false),
+    };
+    let value = match &constant.value {
+        Value::Int(value) => {
+            let literal = Literal::u64_unsuffixed(value.unsigned_abs());
+            if *value < 0 { quote!(-#literal) } else { quote!(#literal) }
+        }
+        Value::Float(value) => {
+            let literal = Literal::f64_unsuffixed(value.abs());
+            if value.is_sign_negative() { quote!(-#literal) } else { quote!(#literal) }
+        }
+        Value::Bool(value) => quote!(#value),
+        Value::String(value) => quote!(#value),
+    };
+
+    quote! {
+        pub const #name: #typ = #value;
+    }
+}
+
+pub(crate) fn rust_name(
+    name: &str,
     is_function: bool,
 ) -> String {
     if is_function {
-        def.name.to_case(Case::Pascal)
+        escape_ident(&name.to_case(Case::Pascal)).into_owned()
     } else {
-        def.name.clone()
+        escape_ident(name).into_owned()
     }
 }
 
-fn generate_definition_id(
-    o: &mut Output,
+fn get_definition_name(
     def: &DefinitionCore,
-) {
-    o.write(&def.id.to_string());
-    o.write("_u32");
+    is_function: bool,
+) -> Ident {
+    if is_function {
+        ident(&def.name.to_case(Case::Pascal))
+    } else {
+        ident(&def.name)
+    }
+}
+
+fn get_field_name(field: &Field) -> Ident {
+    ident(&field.name)
+}
+
+fn get_local_name(field: &Field) -> Ident {
+    format_ident!("{}_", get_field_name(field).unraw())
+}
+
+fn generate_default_doc(field: &Field) -> Option<TokenStream> {
+    let doc = format!(" Usually `{}`.", field.default.as_ref()?);
+    Some(quote!(#[doc = #doc]))
+}
+
+fn generate_definition_id(def: &DefinitionCore) -> Literal {
+    Literal::u32_suffixed(def.id)
 }
 
+// Generic arguments are written with `::<` so a type can start an expression,
+// as in `Vec::<u8>::deserialize(src)`. Paths are absolute, since schema types
+// named like `Vec` or `Result` shadow the prelude in `types`.
 fn generate_type(
-    o: &mut Output,
+    cx: &Context,
     typ: &Type,
     in_mod: bool,
-) {
+) -> TokenStream {
     match typ {
-        Type::Int32 => o.write("i32"),
-        Type::Int64 => o.write("i64"),
-        Type::Float => o.write("f64"),
-        Type::Bool => o.write("bool"),
-        Type::String => o.write("String"),
-        Type::Bytes => o.write("Vec::<u8>"),
-        Type::Time => o.write("std::time::SystemTime"),
+        Type::Int32 => quote!(::core::primitive::i32),
+        Type::Int64 => quote!(::core::primitive::i64),
+        Type::Float => quote!(::core::primitive::f64),
+        Type::Bool => quote!(::core::primitive::bool),
+        Type::String => quote!(::std::string::String),
+        Type::Bytes => quote!(::std::vec::Vec::<::core::primitive::u8>),
+        Type::Time => quote!(::std::time::SystemTime),
+        Type::Void => quote!(()),
         Type::Vector(typ) => {
-            o.write("Vec::<");
-            generate_type(o, typ, in_mod);
-            o.write(">");
+            let typ = generate_type(cx, typ, in_mod);
+            quote!(::std::vec::Vec::<#typ>)
         }
         Type::Option(typ) => {
-            o.write("Option::<");
-            generate_type(o, typ, in_mod);
-            o.write(">");
+            let typ = generate_type(cx, typ, in_mod);
+            quote!(::core::option::Option::<#typ>)
         }
         Type::Defined(name) => {
-            if in_mod {
-                o.write("super::");
-            }
-            o.write("types::");
-            o.write(name);
+            let module = match cx.externs.get(name.as_str()) {
+                Some(module) => quote!(#module::),
+                None if in_mod => quote!(super::),
+                None => quote!(),
+            };
+            let name = ident(name);
+            quote!(#module types::#name)
         }
-    };
+    }
+}
+
+fn generate_cfg(def: &DefinitionCore) -> Option<TokenStream> {
+    def.feature.as_ref().map(|feature| quote!(#[cfg(feature = #feature)]))
+}
+
+fn is_gated(definitions: &[&DefinitionCore]) -> bool {
+    definitions.iter().any(|def| def.feature.is_some())
+}
+
+// Features can disable every variant, a reference to the enum still needs an arm.
+fn generate_gated_fallback(definitions: &[&DefinitionCore]) -> Option<TokenStream> {
+    is_gated(definitions).then(|| quote! {
+        #[allow(unreachable_patterns)]
+        _ => unreachable!(),
+    })
 }
 
 fn generate_enum(
-    o: &mut Output,
-    name: &str,
+    cx: &Context,
+    name: &Ident,
     is_function: bool,
+    in_mod: bool,
+    impls: Impls,
     definitions: &[&DefinitionCore],
-) {
-    o.write_line(|o| o.write("#[derive(Debug, Clone, PartialEq)]"));
-    o.write_line(|o| {
-        o.write("pub enum ");
-        o.write(name);
-        o.write(" {");
+) -> TokenStream {
+    let runtime = &cx.runtime;
+
+    let variants = definitions.iter().map(|&def| {
+        let cfg = generate_cfg(def);
+        let variant = get_definition_name(def, is_function);
+        if is_function {
+            return quote! {
+                #cfg
+                #variant(self::functions::#variant),
+            };
+        }
+
+        let fields = def.fields.iter().map(|field| {
+            let name = get_field_name(field);
+            let typ = generate_type(cx, &field.typ, in_mod);
+            let doc = generate_default_doc(field);
+            quote!(#doc #name: #typ,)
+        });
+        quote! {
+            #cfg
+            #variant {
+                #(#fields)*
+            },
+        }
     });
-    o.with_indent(|o| {
-        for &def in definitions {
-            if is_function {
-                o.write_line(|o| {
-                    o.write(&get_definition_name(def, is_function));
-                    o.write("(self::functions::");
-                    o.write(&get_definition_name(def, is_function));
-                    o.write("),");
-                });
-            } else {
-                o.write_line(|o| {
-                    o.write(&get_definition_name(def, is_function));
-                    o.write(" {");
-                });
-                o.with_indent(|o| {
-                    for field in &def.fields {
-                        o.write_line(|o| {
-                            o.write(&field.name);
-                            o.write(": ");
-                            generate_type(o, &field.typ, false);
-                            o.write(",");
-                        });
+
+    let serialize = (impls.serialize && !is_function).then(|| {
+        if definitions.is_empty() {
+            return quote! {
+                impl #runtime::Serialize for #name {
+                    fn serialize(&self, _dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+                        match *self {}
                     }
-                });
-                o.write_line(|o| o.write("},"));
+                }
+            };
+        }
+
+        let arms = definitions.iter().map(|&def| {
+            let cfg = generate_cfg(def);
+            let variant = get_definition_name(def, is_function);
+            let fields = generate_field_bindings(def);
+            let id = generate_definition_id(def);
+            let serialize_fields = generate_serialize_fields(def, |field| {
+                let local = get_local_name(field);
+                quote!(#local)
+            });
+            quote! {
+                #cfg
+                Self::#variant { #fields } => {
+                    #id.serialize(dst);
+                    #serialize_fields
+                }
+            }
+        });
+        let fallback = generate_gated_fallback(definitions);
+        // `dst` is unused when features disable every definition.
+        let allow = is_gated(definitions).then(|| quote!(#[allow(unused_variables)]));
+
+        quote! {
+            impl #runtime::Serialize for #name {
+                #allow
+                fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+                    match self {
+                        #(#arms)*
+                        #fallback
+                    };
+                }
             }
         }
     });
-    o.write_line(|o| o.write("}"));
 
-    if !is_function {
-        o.write("\n");
+    let deserialize = impls.deserialize.then(|| {
+        let body = if definitions.is_empty() {
+            quote!(::core::result::Result::Err(#runtime::deserialize::Error::UnexpectedDefinitionId(id)))
+        } else {
+            let arms = definitions.iter().map(|&def| {
+                let cfg = generate_cfg(def);
+                let variant = get_definition_name(def, is_function);
+                let id = generate_definition_id(def);
+                if is_function {
+                    return quote! {
+                        #cfg
+                        #id => Self::#variant(self::functions::#variant::deserialize(src)?),
+                    };
+                }
 
-        o.write_line(|o| {
-            o.write("impl crate::Serialize for ");
-            o.write(name);
-            o.write(" {");
-        });
-        o.with_indent(|o| {
-            o.write_line(|o| o.write("fn serialize(&self, dst: &mut Vec<u8>) {"));
-            o.with_indent(|o| {
-                o.write_line(|o| o.write("match self {"));
-                o.with_indent(|o| {
-                    for &def in definitions {
-                        o.write_line(|o| {
-                            o.write("Self::");
-                            o.write(&get_definition_name(def, is_function));
-                            o.write(" { ");
-                            for field in &def.fields {
-                                o.write(&field.name);
-                                o.write(": ");
-                                o.write(&field.name);
-                                o.write("_, ");
-                            }
-                            o.write("} => {");
-                        });
-                        o.with_indent(|o| {
-                            o.write_line(|o| {
-                                generate_definition_id(o, def);
-                                o.write(".serialize(dst);");
-                            });
-                            for field in &def.fields {
-                                o.write_line(|o| {
-                                    o.write(&field.name);
-                                    o.write("_.serialize(dst);");
-                                });
-                            }
-                        });
-                        o.write_line(|o| o.write("}"));
+                let deserialize_fields = generate_deserialize_fields(cx, def, in_mod);
+                let fields = generate_field_bindings(def);
+                quote! {
+                    #cfg
+                    #id => {
+                        #deserialize_fields
+                        Self::#variant { #fields }
                     }
-                });
-                o.write_line(|o| o.write("};"));
+                }
             });
-            o.write_line(|o| o.write("}"));
+            quote! {
+                ::core::result::Result::Ok(match id {
+                    #(#arms)*
+                    _ => return ::core::result::Result::Err(#runtime::deserialize::Error::UnexpectedDefinitionId(id)),
+                })
+            }
+        };
+        // `Ok` is unreachable when features disable every definition.
+        let allow = is_gated(definitions).then(|| quote!(#[allow(unreachable_code)]));
+
+        quote! {
+            impl #runtime::Deserialize for #name {
+                #allow
+                fn deserialize(
+                    src: &mut &[::core::primitive::u8],
+                ) -> ::core::result::Result<Self, #runtime::deserialize::Error> {
+                    let id = ::core::primitive::u32::deserialize(src)?;
+                    #body
+                }
+            }
+        }
+    });
+
+    quote! {
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum #name {
+            #(#variants)*
+        }
+
+        #serialize
+
+        #deserialize
+    }
+}
+
+// `field: field_` for every field, to destructure or build a definition.
+fn generate_field_bindings(def: &DefinitionCore) -> TokenStream {
+    let fields = def.fields.iter().map(|field| {
+        let name = get_field_name(field);
+        let local = get_local_name(field);
+        quote!(#name: #local,)
+    });
+    quote!(#(#fields)*)
+}
+
+// Extension fields are written after the others as a length-delimited
+// block, so readers can skip fields they don't know.
+fn generate_serialize_fields(
+    def: &DefinitionCore,
+    value: impl Fn(&Field) -> TokenStream,
+) -> TokenStream {
+    let fields = def.fields.iter()
+        .filter(|field| !field.extension)
+        .map(&value);
+    let fields = quote!(#(#fields.serialize(dst);)*);
+
+    if !def.extensible {
+        return fields;
+    }
+    let extensions = def.fields.iter()
+        .filter(|field| field.extension)
+        .map(&value)
+        .collect::<Vec<_>>();
+    if extensions.is_empty() {
+        return quote! {
+            #fields
+            ::std::vec::Vec::<::core::primitive::u8>::new().serialize(dst);
+        };
+    }
+
+    quote! {
+        #fields
+        let mut extensions = ::std::vec::Vec::<::core::primitive::u8>::new();
+        #(#extensions.serialize(&mut extensions);)*
+        extensions.serialize(dst);
+    }
+}
+
+fn generate_deserialize_fields(
+    cx: &Context,
+    def: &DefinitionCore,
+    in_mod: bool,
+) -> TokenStream {
+    let runtime = &cx.runtime;
+    let fields = def.fields.iter()
+        .filter(|field| !field.extension)
+        .map(|field| {
+            let local = get_local_name(field);
+            let typ = generate_type(cx, &field.typ, in_mod);
+            quote!(let #local = #typ::deserialize(src)?;)
         });
-        o.write_line(|o| o.write("}"));
+    let fields = quote!(#(#fields)*);
+
+    if !def.extensible {
+        return fields;
+    }
+    let extensions = def.fields.iter()
+        .filter(|field| field.extension)
+        .map(|field| {
+            let local = get_local_name(field);
+            let typ = generate_type(cx, &field.typ, in_mod);
+            quote!(let #local = #runtime::deserialize::extension::<#typ>(src)?;)
+        })
+        .collect::<Vec<_>>();
+    if extensions.is_empty() {
+        return quote! {
+            #fields
+            ::std::vec::Vec::<::core::primitive::u8>::deserialize(src)?;
+        };
     }
 
-    o.write("\n");
+    quote! {
+        #fields
+        let extensions = ::std::vec::Vec::<::core::primitive::u8>::deserialize(src)?;
+        let src = &mut extensions.as_slice();
+        #(#extensions)*
+    }
+}
+
+// A trait with accessors for the group's fields, implemented for every
+// function and for every enum whose constructors all include the group.
+fn generate_group(
+    cx: &Context,
+    schema: &Schema,
+    group: &GroupDefinition,
+) -> TokenStream {
+    let name = ident(&group.name);
+    let includes = |def: &DefinitionCore| def.fields.iter()
+        .any(|field| field.group.as_ref() == Some(&group.name));
 
-    o.write_line(|o| {
-        o.write("impl crate::Deserialize for ");
-        o.write(name);
-        o.write(" {");
+    let accessors = group.fields.iter().map(|field| {
+        let accessor = generate_accessor(cx, field);
+        quote!(#accessor;)
     });
-    o.with_indent(|o| {
-        o.write_line(|o| o.write("fn deserialize(src: &mut &[u8]) -> Result<Self, crate::deserialize::Error> {"));
-        o.with_indent(|o| {
-            o.write_line(|o| o.write("let id = u32::deserialize(src)?;"));
-            o.write("\n");
-            o.write_line(|o| o.write("Ok(match id {"));
-            o.with_indent(|o| {
-                for &def in definitions {
-                    if is_function {
-                        o.write_line(|o| {
-                            generate_definition_id(o, def);
-                            o.write(" => Self::");
-                            o.write(&get_definition_name(def, is_function));
-                            o.write("(self::functions::");
-                            o.write(&get_definition_name(def, is_function));
-                            o.write("::deserialize(src)?),");
-                        });
-                    } else {
-                        o.write_line(|o| {
-                            generate_definition_id(o, def);
-                            o.write(" => {");
-                        });
-                        o.with_indent(|o| {
-                            for field in &def.fields {
-                                o.write_line(|o| {
-                                    o.write("let ");
-                                    o.write(&field.name);
-                                    o.write("_ = ");
-                                    generate_type(o, &field.typ, false);
-                                    o.write("::deserialize(src)?;");
-                                });
-                            }
-                            o.write("\n");
-                            o.write_line(|o| {
-                                o.write("Self::");
-                                o.write(&get_definition_name(def, is_function));
-                                o.write(" { ");
-                                for field in &def.fields {
-                                    o.write(&field.name);
-                                    o.write(": ");
-                                    o.write(&field.name);
-                                    o.write("_, ");
-                                }
-                                o.write("}");
-                            });
-                        });
-                        o.write_line(|o| o.write("}"));
+
+    let enums = enums(schema).into_iter()
+        .map(|(name, definitions)| {
+            let name = ident(name);
+            (quote!(super::types::#name), definitions)
+        });
+    let errors = (quote!(super::Error), schema.errors.iter().map(|def| &def.core).collect::<Vec<_>>());
+
+    let enum_impls = enums.chain([errors])
+        .filter(|(_, definitions)| !definitions.is_empty() && definitions.iter().all(|def| includes(def)))
+        .map(|(path, definitions)| {
+            generate_accessors_impl(cx, &name, &path, &group.fields, |field| {
+                let field = get_field_name(field);
+                let arms = definitions.iter().map(|&def| {
+                    let cfg = generate_cfg(def);
+                    let variant = get_definition_name(def, false);
+                    quote! {
+                        #cfg
+                        Self::#variant { #field, .. } => #field,
+                    }
+                });
+                let fallback = generate_gated_fallback(&definitions);
+                quote! {
+                    match self {
+                        #(#arms)*
+                        #fallback
                     }
                 }
-                o.write_line(|o| o.write("_ => return Err(crate::deserialize::Error::UnexpectedDefinitionId(id)),"));
-            });
-            o.write_line(|o| o.write("})"));
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let records = schema.records.iter()
+        .filter(|def| includes(&def.core))
+        .map(|def| {
+            let name = get_definition_name(&def.core, false);
+            (quote!(super::types::#name), &def.core)
+        });
+    let functions = schema.functions.iter()
+        .filter(|def| includes(&def.core))
+        .map(|def| {
+            let name = get_definition_name(&def.core, true);
+            (quote!(super::functions::#name), &def.core)
         });
-        o.write_line(|o| o.write("}"));
+
+    let struct_impls = records.chain(functions).map(|(path, def)| {
+        let cfg = generate_cfg(def);
+        let accessors = generate_accessors_impl(cx, &name, &path, &group.fields, |field| {
+            let field = get_field_name(field);
+            quote!(&self.#field)
+        });
+        quote! {
+            #cfg
+            #accessors
+        }
     });
-    o.write_line(|o| o.write("}"));
+
+    quote! {
+        pub trait #name {
+            #(#accessors)*
+        }
+
+        #(#enum_impls)*
+
+        #(#struct_impls)*
+    }
+}
+
+fn generate_accessor(
+    cx: &Context,
+    field: &Field,
+) -> TokenStream {
+    let name = get_field_name(field);
+    let typ = generate_type(cx, &field.typ, true);
+    quote!(fn #name(&self) -> &#typ)
+}
+
+fn generate_accessors_impl(
+    cx: &Context,
+    name: &Ident,
+    path: &TokenStream,
+    fields: &[Field],
+    body: impl Fn(&Field) -> TokenStream,
+) -> TokenStream {
+    let accessors = fields.iter().map(|field| {
+        let accessor = generate_accessor(cx, field);
+        let body = body(field);
+        quote! {
+            #accessor {
+                #body
+            }
+        }
+    });
+
+    quote! {
+        impl #name for #path {
+            #(#accessors)*
+        }
+    }
 }
 
 fn generate_definition(
-    o: &mut Output,
+    cx: &Context,
     def: &DefinitionCore,
     ret: Option<&Type>,
-) {
-    o.write_line(|o| o.write("#[derive(Debug, Clone, PartialEq)]"));
-    o.write_line(|o| {
-        o.write("pub struct ");
-        o.write(&get_definition_name(def, ret.is_some()));
-        o.write(" {");
+    impls: Impls,
+) -> TokenStream {
+    let runtime = &cx.runtime;
+    let cfg = generate_cfg(def);
+    let name = get_definition_name(def, ret.is_some());
+
+    let fields = def.fields.iter().map(|field| {
+        let name = get_field_name(field);
+        let typ = generate_type(cx, &field.typ, true);
+        let doc = generate_default_doc(field);
+        quote!(#doc pub #name: #typ,)
     });
-    o.with_indent(|o| {
-        for field in &def.fields {
-            o.write_line(|o| {
-                o.write("pub ");
-                o.write(&field.name);
-                o.write(": ");
-                generate_type(o, &field.typ, true);
-                o.write(",");
-            });
+
+    let serialize = impls.serialize.then(|| {
+        let dst = if ret.is_none() && def.fields.is_empty() && !def.extensible {
+            ident("_dst")
+        } else {
+            ident("dst")
+        };
+        let id = ret.is_some().then(|| {
+            let id = generate_definition_id(def);
+            quote!(#id.serialize(dst);)
+        });
+        let serialize_fields = generate_serialize_fields(def, |field| {
+            let name = get_field_name(field);
+            quote!(self.#name)
+        });
+        quote! {
+            #cfg
+            impl #runtime::Serialize for #name {
+                fn serialize(&self, #dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+                    #id
+                    #serialize_fields
+                }
+            }
         }
     });
-    o.write_line(|o| o.write("}"));
 
-    o.write("\n");
-
-    o.write_line(|o| {
-        o.write("impl crate::Serialize for ");
-        o.write(&get_definition_name(def, ret.is_some()));
-        o.write(" {");
-    });
-    o.with_indent(|o| {
-        o.write_line(|o| o.write("fn serialize(&self, dst: &mut Vec<u8>) {"));
-        o.with_indent(|o| {
-            if ret.is_some() {
-                o.write_line(|o| {
-                    generate_definition_id(o, def);
-                    o.write(".serialize(dst);");
-                });
-            }
-            for field in &def.fields {
-                o.write_line(|o| {
-                    o.write("self.");
-                    o.write(&field.name);
-                    o.write(".serialize(dst);");
-                });
+    let deserialize = impls.deserialize.then(|| {
+        let src = if def.fields.is_empty() && !def.extensible { ident("_src") } else { ident("src") };
+        let deserialize_fields = generate_deserialize_fields(cx, def, true);
+        let fields = generate_field_bindings(def);
+        quote! {
+            #cfg
+            impl #runtime::Deserialize for #name {
+                fn deserialize(
+                    #src: &mut &[::core::primitive::u8],
+                ) -> ::core::result::Result<Self, #runtime::deserialize::Error> {
+                    #deserialize_fields
+                    ::core::result::Result::Ok(Self { #fields })
+                }
             }
-        });
-        o.write_line(|o| o.write("}"));
+        }
     });
-    o.write_line(|o| o.write("}"));
-
-    o.write("\n");
 
-    o.write_line(|o| {
-        o.write("impl crate::Deserialize for ");
-        o.write(&get_definition_name(def, ret.is_some()));
-        o.write(" {");
-    });
-    o.with_indent(|o| {
-        o.write_line(|o| o.write("fn deserialize(src: &mut &[u8]) -> Result<Self, crate::deserialize::Error> {"));
-        o.with_indent(|o| {
-            for field in &def.fields {
-                o.write_line(|o| {
-                    o.write("let ");
-                    o.write(&field.name);
-                    o.write("_ = ");
-                    generate_type(o, &field.typ, true);
-                    o.write("::deserialize(src)?;");
-                });
+    let call = ret.map(|ret| {
+        let ret = generate_type(cx, ret, true);
+        quote! {
+            #cfg
+            impl #runtime::Call for #name {
+                type Return = #ret;
             }
-            o.write("\n");
-            o.write_line(|o| {
-                o.write("Ok(Self { ");
-                for field in &def.fields {
-                    o.write(&field.name);
-                    o.write(": ");
-                    o.write(&field.name);
-                    o.write("_, ");
-                }
-                o.write("})");
-            });
-        });
-        o.write_line(|o| o.write("}"));
+        }
     });
-    o.write_line(|o| o.write("}"));
 
-    if let Some(ret) = ret {
-        o.write("\n");
+    quote! {
+        #cfg
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #name {
+            #(#fields)*
+        }
+
+        #serialize
 
-        o.write_line(|o| {
-            o.write("impl crate::Call for ");
-            o.write(&get_definition_name(def, true));
-            o.write(" {");
-        });
-        o.with_indent(|o| {
-            o.write_line(|o| {
-                o.write("type Return = ");
-                generate_type(o, ret, true);
-                o.write(";");
-            });
-        });
-        o.write_line(|o| o.write("}"));
+        #deserialize
+
+        #call
     }
 }
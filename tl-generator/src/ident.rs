@@ -0,0 +1,53 @@
+use proc_macro2::{Ident, Span};
+use std::borrow::Cow;
+
+const KEYWORDS: [&str; 52] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop",
+    "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract",
+    "become", "box", "do", "final", "macro", "override", "priv", "try", "typeof", "unsized",
+    "virtual", "yield",
+];
+
+// These can't be raw identifiers.
+const RESERVED: [&str; 4] = ["self", "Self", "super", "crate"];
+
+// Reserved words get a `_` appended, and so do names that already end with
+// one after a reserved word: `self` becomes `self_` and `self_` becomes
+// `self__`, so escaped names never collide.
+pub fn escape_ident(name: &str) -> Cow<'_, str> {
+    if RESERVED.contains(&name.trim_end_matches('_')) {
+        Cow::Owned(format!("{name}_"))
+    } else if KEYWORDS.contains(&name) {
+        Cow::Owned(format!("r#{name}"))
+    } else {
+        Cow::Borrowed(name)
+    }
+}
+
+pub(crate) fn ident(name: &str) -> Ident {
+    match escape_ident(name).strip_prefix("r#") {
+        Some(name) => Ident::new_raw(name, Span::call_site()),
+        None => Ident::new(&escape_ident(name), Span::call_site()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keywords() {
+        assert_eq!(escape_ident("user_id"), "user_id");
+        assert_eq!(escape_ident("type"), "r#type");
+        assert_eq!(escape_ident("fn"), "r#fn");
+        assert_eq!(escape_ident("gen"), "r#gen");
+        assert_eq!(escape_ident("self"), "self_");
+        assert_eq!(escape_ident("Self"), "Self_");
+        assert_eq!(escape_ident("crate"), "crate_");
+        assert_eq!(escape_ident("self_"), "self__");
+        assert_eq!(escape_ident("super__"), "super___");
+        assert_eq!(escape_ident("selfie_"), "selfie_");
+    }
+}
@@ -1,11 +1,285 @@
-mod output;
 mod generate;
+mod ident;
+mod describe;
+mod shake;
 
-use generate::generate_schema;
-use output::Output;
+use generate::{generate_description, generate_schema, rust_name, Context};
+pub use ident::escape_ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+use tl_parser::graph::{Graph, Node};
 
-pub fn generate(schema: &tl_parser::Schema) -> String {
-    let mut output = Output::new(4, 0);
-    generate_schema(&mut output, schema);
-    output.destruct()
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("invalid runtime path: {path}")]
+    InvalidRuntime { path: String },
+
+    #[error("invalid module path of schema {schema}: {path}")]
+    InvalidExternModule { schema: String, path: String },
+
+    #[error("generated code is not valid Rust: {0}")]
+    InvalidCode(String),
+
+    #[error("line {}: functions {other} and {name} are both generated as {rust_name}", .span.line)]
+    NameCollision { span: tl_parser::Span, name: String, other: String, rust_name: String },
+}
+
+// The side generated code runs on, clients write functions and read results
+// and errors, servers the other way around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Role {
+    Client,
+    Server,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    // Path of `tl-types` or a module re-exporting it.
+    pub runtime: String,
+    // Paths of the modules generated from other schemas, by schema name,
+    // extern types of schemas missing here are looked up in `crate::<schema>`.
+    pub extern_modules: HashMap<String, String>,
+    // Names of the functions to generate, `prefix*` selects every function
+    // starting with `prefix`. Types and groups no kept definition uses are left out.
+    pub functions: Option<Vec<String>>,
+    // Only `Serialize` and `Deserialize` impls this side needs are generated.
+    pub role: Role,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            runtime: "crate".to_owned(),
+            extern_modules: HashMap::new(),
+            functions: None,
+            role: Role::Both,
+        }
+    }
+}
+
+pub fn generate(schema: &tl_parser::Schema) -> Result<String, Error> {
+    generate_with_options(schema, &Options::default())
+}
+
+pub fn generate_with_options(schema: &tl_parser::Schema, options: &Options) -> Result<String, Error> {
+    let tokens = generate_tokens(schema, options)?;
+    let file = syn::parse2(tokens).map_err(|err| Error::InvalidCode(err.to_string()))?;
+    Ok(prettyplease::unparse(&file))
+}
+
+// The code before formatting, for proc macros. Fails if the runtime or an
+// extern module isn't a Rust path.
+pub fn generate_tokens(schema: &tl_parser::Schema, options: &Options) -> Result<TokenStream, Error> {
+    check_function_names(schema)?;
+    let mut externs = HashMap::new();
+    for def in &schema.externs {
+        let path = extern_module(&def.schema, options);
+        let Ok(module) = syn::parse_str::<syn::Path>(&path) else {
+            return Err(Error::InvalidExternModule { schema: def.schema.clone(), path });
+        };
+        externs.insert(def.name.as_str(), module);
+    }
+    let Ok(runtime) = syn::parse_str::<syn::Path>(&options.runtime) else {
+        return Err(Error::InvalidRuntime { path: options.runtime.clone() });
+    };
+    let graph = Graph::new(schema);
+    let requests = schema.functions.iter()
+        .flat_map(|def| &def.core.fields)
+        .filter_map(|field| field.typ.defined());
+    let responses = schema.functions.iter()
+        .filter_map(|def| def.ret.defined())
+        .chain(schema.errors.iter().flat_map(|def| &def.core.fields).filter_map(|field| field.typ.defined()));
+    let cx = Context {
+        runtime,
+        externs,
+        role: options.role,
+        requests: reached(&graph, requests),
+        responses: reached(&graph, responses),
+    };
+
+    // The description is always of the whole schema, so fingerprints match
+    // the peer's.
+    let description = generate_description(schema);
+    let items = match &options.functions {
+        Some(functions) => generate_schema(&cx, &shake::shake(schema, functions)),
+        None => generate_schema(&cx, schema),
+    };
+    Ok(quote!(#description #items))
+}
+
+// Function names are converted to PascalCase, so `get_user` and `getUser`
+// would both become `GetUser`.
+fn check_function_names(schema: &tl_parser::Schema) -> Result<(), Error> {
+    let mut names = HashMap::new();
+    for def in &schema.functions {
+        let rust_name = rust_name(&def.core.name, true);
+        if let Some(other) = names.insert(rust_name.clone(), &def.core.name) {
+            return Err(Error::NameCollision {
+                span: def.core.name_span,
+                name: def.core.name.clone(),
+                other: other.clone(),
+                rust_name,
+            });
+        }
+    }
+    Ok(())
+}
+
+// The types and every type they contain.
+fn reached<'a>(graph: &Graph<'a>, types: impl Iterator<Item = &'a str>) -> HashSet<&'a str> {
+    let types = types.collect::<Vec<_>>();
+    let dependencies = graph.transitive_dependencies(types.iter().map(|name| Node::Type(name)));
+    types.into_iter().chain(dependencies).collect()
+}
+
+fn extern_module(schema: &str, options: &Options) -> String {
+    match options.extern_modules.get(schema) {
+        Some(module) => module.clone(),
+        None => format!("crate::{}", ident::escape_ident(schema)),
+    }
+}
+
+pub fn extern_path(schema: &str, name: &str, options: &Options) -> String {
+    format!("{}::{}", extern_module(schema, options), type_path(name))
+}
+
+pub fn type_path(r#enum: &str) -> String {
+    format!("types::{}", rust_name(r#enum, false))
+}
+
+pub fn constructor_path(r#enum: &str, name: &str) -> String {
+    format!("types::{}::{}", rust_name(r#enum, false), rust_name(name, false))
+}
+
+pub fn error_path(name: &str) -> String {
+    format!("Error::{}", rust_name(name, false))
+}
+
+pub fn function_path(name: &str) -> String {
+    format!("functions::{}", rust_name(name, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::{env, fs};
+    use tl_parser::parse_schema;
+
+    #[test]
+    fn roles() {
+        let schema = parse_schema("\
+type Photo data:bytes = Photo
+type User id:int64 = User
+error NotFound id:int64
+func upload_photo photo:Photo = User
+").unwrap();
+        let generate = |role| generate_with_options(&schema, &Options { role, ..Options::default() }).unwrap();
+        let impls = |code: String| {
+            let mut impls = code.lines()
+                .filter_map(|line| line.trim().strip_prefix("impl crate::").map(str::to_owned))
+                .collect::<Vec<_>>();
+            impls.sort();
+            impls
+        };
+
+        assert_eq!(impls(generate(Role::Client)), [
+            "Call for UploadPhoto {",
+            "Deserialize for Error {",
+            "Deserialize for User {",
+            "Serialize for Photo {",
+            "Serialize for UploadPhoto {",
+        ]);
+        assert_eq!(impls(generate(Role::Server)), [
+            "Call for UploadPhoto {",
+            "Deserialize for Function {",
+            "Deserialize for Photo {",
+            "Deserialize for UploadPhoto {",
+            "Serialize for Error {",
+            "Serialize for User {",
+        ]);
+        assert_eq!(impls(generate(Role::Both)).len(), 10);
+    }
+
+    #[test]
+    fn paths() {
+        let schema = parse_schema("extern common Money\nrecord Price amount:Money\n").unwrap();
+        let generate = |runtime: &str, module: &str| generate_with_options(&schema, &Options {
+            runtime: runtime.to_owned(),
+            extern_modules: HashMap::from([("common".to_owned(), module.to_owned())]),
+            ..Options::default()
+        });
+
+        assert!(generate("::tl_types", "super::common").unwrap().contains("super::common::types::Money"));
+        assert_eq!(
+            generate("tl types", "crate::common").unwrap_err().to_string(),
+            "invalid runtime path: tl types",
+        );
+        assert_eq!(
+            generate("crate", "crate::common::").unwrap_err().to_string(),
+            "invalid module path of schema common: crate::common::",
+        );
+    }
+
+    #[test]
+    fn function_names() {
+        let schema = parse_schema("func get_user id:int64 = int64\nfunc getUser id:int64 = int64\n").unwrap();
+        assert_eq!(
+            generate(&schema).unwrap_err().to_string(),
+            "line 2: functions get_user and getUser are both generated as GetUser",
+        );
+    }
+
+    // Generated code is compared with `snapshots/<name>.rs`, rerun with
+    // `TL_UPDATE_SNAPSHOTS=1` to accept changes.
+    #[test]
+    fn snapshots() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("snapshots");
+        let update = env::var_os("TL_UPDATE_SNAPSHOTS").is_some();
+        let snapshots = [
+            ("types", "types", Role::Both),
+            ("types", "types.client", Role::Client),
+            ("types", "types.server", Role::Server),
+            ("definitions", "definitions", Role::Both),
+            ("features", "features", Role::Both),
+        ];
+
+        for (schema, name, role) in snapshots {
+            let source = fs::read_to_string(dir.join(format!("{schema}.tl"))).unwrap();
+            let schema = parse_schema(&source).unwrap();
+            let code = generate_with_options(&schema, &Options { role, ..Options::default() }).unwrap();
+
+            let path = dir.join(format!("{name}.rs"));
+            if update {
+                fs::write(&path, code).unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&path).unwrap_or_default();
+            assert!(code == expected, "snapshot {name} is out of date, rerun with TL_UPDATE_SNAPSHOTS=1");
+        }
+    }
+
+    // `tl-types` checks in the code of the meta-schema so it doesn't build the
+    // generator, this keeps it up to date the same way as the snapshots.
+    #[test]
+    fn meta() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tl-types/src/meta.rs");
+        let header = "\
+// Generated from tl-parser's meta.tl, the `meta` test of tl-generator checks it's up to date.
+// Rerun it with `TL_UPDATE_SNAPSHOTS=1` after changing the meta-schema.
+
+";
+        let code = header.to_owned() + &generate(&parse_schema(tl_parser::META_SCHEMA).unwrap()).unwrap();
+
+        if env::var_os("TL_UPDATE_SNAPSHOTS").is_some() {
+            fs::write(&path, code).unwrap();
+            return;
+        }
+        let expected = fs::read_to_string(&path).unwrap();
+        assert!(code == expected, "tl-types/src/meta.rs is out of date, rerun with TL_UPDATE_SNAPSHOTS=1");
+    }
 }
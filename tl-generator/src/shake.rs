@@ -0,0 +1,74 @@
+use tl_parser::graph::{Graph, Node};
+use tl_parser::Schema;
+
+// Keeps the selected functions and the types they reach. All errors are kept,
+// any function can fail with any of them, and groups whose accessors would
+// refer to a dropped type are left out.
+pub(crate) fn shake(schema: &Schema, selection: &[String]) -> Schema {
+    let selected = |name: &str| selection.iter().any(|pattern| match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    });
+
+    let graph = Graph::new(schema);
+    let roots = schema.functions.iter()
+        .filter(|def| selected(&def.core.name))
+        .map(|def| Node::Function(&def.core.name))
+        .chain(schema.errors.iter().map(|def| Node::Error(&def.core.name)));
+    let reached = graph.transitive_dependencies(roots);
+
+    Schema {
+        constants: schema.constants.clone(),
+        externs: schema.externs.iter().filter(|def| reached.contains(&def.name.as_str())).cloned().collect(),
+        groups: schema.groups.iter()
+            .filter(|def| def.fields.iter().all(|field| {
+                field.typ.defined().is_none_or(|name| reached.contains(&name))
+            }))
+            .cloned()
+            .collect(),
+        records: schema.records.iter().filter(|def| reached.contains(&def.core.name.as_str())).cloned().collect(),
+        types: schema.types.iter().filter(|def| reached.contains(&def.r#enum.as_str())).cloned().collect(),
+        errors: schema.errors.clone(),
+        functions: schema.functions.iter().filter(|def| selected(&def.core.name)).cloned().collect(),
+        reserved: schema.reserved.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tl_parser::parse_schema;
+
+    #[test]
+    fn selection() {
+        let schema = parse_schema("\
+record Point x:float y:float
+type Photo data:bytes = Photo
+type User id:int64 photo:Photo? = User
+group Owned owner:User
+type Chat id:int64 ..Owned = Chat
+type Place at:Point = Place
+error NotFound what:Place
+func get_user id:int64 = User
+func get_users ids:[int64] = [User]
+func get_chat id:int64 = Chat
+func send_photo photo:Photo = void
+").unwrap();
+
+        fn names(schema: &Schema) -> Vec<&str> {
+            let records = schema.records.iter().map(|def| def.core.name.as_str());
+            let types = schema.types.iter().map(|def| def.core.name.as_str());
+            let functions = schema.functions.iter().map(|def| def.core.name.as_str());
+            records.chain(types).chain(functions).collect()
+        }
+
+        let shaken = shake(&schema, &["get_user*".to_owned()]);
+        assert_eq!(names(&shaken), ["Point", "Photo", "User", "Place", "get_user", "get_users"]);
+        assert_eq!(shaken.errors, schema.errors);
+        assert_eq!(shaken.groups, schema.groups);
+
+        let shaken = shake(&schema, &["send_photo".to_owned()]);
+        assert_eq!(names(&shaken), ["Point", "Photo", "Place", "send_photo"]);
+        assert_eq!(shaken.groups, vec![]);
+    }
+}
@@ -0,0 +1,292 @@
+use tl_generator::Options;
+use tl_parser::{
+    DefinitionCore, ErrorDefinition, Extern, FunctionDefinition, RecordDefinition, Schema, Span, Type, TypeDefinition,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol<'a> {
+    Enum(&'a str),
+    Extern(&'a Extern),
+    Record(&'a RecordDefinition),
+    Type(&'a TypeDefinition),
+    Error(&'a ErrorDefinition),
+    Function(&'a FunctionDefinition),
+}
+
+impl<'a> Symbol<'a> {
+    // Enums, externs and records are referenced by name in field and return types.
+    pub fn type_name(&self) -> Option<&'a str> {
+        match self {
+            Symbol::Enum(name) => Some(name),
+            Symbol::Extern(def) => Some(&def.name),
+            Symbol::Record(def) => Some(&def.core.name),
+            _ => None,
+        }
+    }
+
+    pub fn span(&self) -> Option<Span> {
+        match self {
+            Symbol::Enum(_) => None,
+            Symbol::Extern(def) => Some(def.name_span),
+            Symbol::Record(def) => Some(def.core.name_span),
+            Symbol::Type(def) => Some(def.core.name_span),
+            Symbol::Error(def) => Some(def.core.name_span),
+            Symbol::Function(def) => Some(def.core.name_span),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reference<'a> {
+    pub name: &'a str,
+    pub span: Span,
+    pub declaration: bool,
+}
+
+pub fn symbol_at(schema: &Schema, line: usize, col: usize) -> Option<Symbol<'_>> {
+    let contains = |span: Span| span.line == line && span.start <= col && col <= span.end;
+
+    let definition = schema.records.iter()
+        .find(|def| contains(def.core.name_span))
+        .map(Symbol::Record)
+        .or_else(|| schema.externs.iter().find(|def| contains(def.name_span)).map(Symbol::Extern))
+        .or_else(|| schema.types.iter().find(|def| contains(def.core.name_span)).map(Symbol::Type))
+        .or_else(|| schema.errors.iter().find(|def| contains(def.core.name_span)).map(Symbol::Error))
+        .or_else(|| schema.functions.iter().find(|def| contains(def.core.name_span)).map(Symbol::Function));
+    if definition.is_some() {
+        return definition;
+    }
+
+    let error = schema.functions.iter()
+        .flat_map(|def| &def.errors)
+        .find(|(_, span)| contains(*span))
+        .and_then(|(name, _)| schema.errors.iter().find(|def| def.core.name == *name));
+    if let Some(def) = error {
+        return Some(Symbol::Error(def));
+    }
+
+    references(schema).into_iter()
+        .find(|reference| contains(reference.span))
+        .map(|reference| {
+            let record = schema.records.iter().find(|def| def.core.name == reference.name).map(Symbol::Record);
+            let r#extern = schema.externs.iter().find(|def| def.name == reference.name).map(Symbol::Extern);
+            record.or(r#extern).unwrap_or(Symbol::Enum(reference.name))
+        })
+}
+
+// Extern and record names, enum declarations after `=` and their uses in
+// field and return types.
+pub fn references(schema: &Schema) -> Vec<Reference<'_>> {
+    let mut references = Vec::new();
+
+    for def in &schema.externs {
+        references.push(Reference { name: &def.name, span: def.name_span, declaration: true });
+    }
+
+    for def in &schema.groups {
+        references.extend(def.fields.iter().filter_map(field_reference));
+    }
+    for def in &schema.records {
+        references.push(Reference { name: &def.core.name, span: def.core.name_span, declaration: true });
+        references.extend(def.core.fields.iter().filter_map(field_reference));
+    }
+    for def in &schema.types {
+        references.push(Reference { name: &def.r#enum, span: def.enum_span, declaration: true });
+        references.extend(def.core.fields.iter().filter_map(field_reference));
+    }
+    for def in &schema.errors {
+        references.extend(def.core.fields.iter().filter_map(field_reference));
+    }
+    for def in &schema.functions {
+        references.extend(def.core.fields.iter().filter_map(field_reference));
+        references.extend(type_reference(def.ret_span, &def.ret));
+    }
+
+    references.sort_by_key(|reference| (reference.span.line, reference.span.start));
+    references
+}
+
+pub fn type_references<'a>(schema: &'a Schema, name: &str) -> Vec<Reference<'a>> {
+    references(schema).into_iter()
+        .filter(|reference| reference.name == name)
+        .collect()
+}
+
+// The declaration of an error and its uses in the error lists of functions.
+pub fn error_references<'a>(schema: &'a Schema, def: &'a ErrorDefinition) -> Vec<Reference<'a>> {
+    let uses = schema.functions.iter()
+        .flat_map(|function| &function.errors)
+        .filter(|(name, _)| *name == def.core.name)
+        .map(|(name, span)| Reference { name, span: *span, declaration: false });
+
+    std::iter::once(Reference { name: &def.core.name, span: def.core.name_span, declaration: true })
+        .chain(uses)
+        .collect()
+}
+
+// References of symbols used by name, `None` for types and functions.
+pub fn symbol_references<'a>(schema: &'a Schema, symbol: Symbol<'a>) -> Option<Vec<Reference<'a>>> {
+    match symbol {
+        Symbol::Error(def) => Some(error_references(schema, def)),
+        _ => Some(type_references(schema, symbol.type_name()?)),
+    }
+}
+
+pub fn constructors<'a>(schema: &'a Schema, r#enum: &str) -> Vec<&'a TypeDefinition> {
+    schema.types.iter()
+        .filter(|def| def.r#enum == r#enum)
+        .collect()
+}
+
+pub fn enums(schema: &Schema) -> Vec<&str> {
+    let mut enums = Vec::new();
+    for def in &schema.types {
+        if !enums.contains(&def.r#enum.as_str()) {
+            enums.push(&def.r#enum);
+        }
+    }
+    enums
+}
+
+pub fn hover(schema: &Schema, symbol: Symbol<'_>, options: &Options) -> String {
+    let definition = |def: &dyn ToString, core: &DefinitionCore, path: String| {
+        format!(
+            "```tl\n{}\n```\n\nid: `0x{:08x}` ({})\n\nRust: `{path}`",
+            def.to_string(),
+            core.id,
+            core.id,
+        )
+    };
+
+    match symbol {
+        Symbol::Enum(name) => {
+            let constructors = constructors(schema, name).iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+            format!("```tl\n{}\n```\n\nRust: `{}`", constructors.join("\n"), tl_generator::type_path(name))
+        }
+        Symbol::Extern(def) => format!(
+            "```tl\n{def}\n```\n\nRust: `{}`",
+            tl_generator::extern_path(&def.schema, &def.name, options),
+        ),
+        Symbol::Record(def) => format!("```tl\n{def}\n```\n\nRust: `{}`", tl_generator::type_path(&def.core.name)),
+        Symbol::Type(def) => {
+            definition(def, &def.core, tl_generator::constructor_path(&def.r#enum, &def.core.name))
+        }
+        Symbol::Error(def) => definition(def, &def.core, tl_generator::error_path(&def.core.name)),
+        Symbol::Function(def) => definition(def, &def.core, tl_generator::function_path(&def.core.name)),
+    }
+}
+
+// Fields spliced from a group are referenced in the group itself.
+fn field_reference(field: &tl_parser::Field) -> Option<Reference<'_>> {
+    if field.group.is_some() {
+        return None;
+    }
+
+    let span = Span {
+        line: field.span.line,
+        start: field.span.start + field.name.len() + 1,
+        end: field.span.end,
+    };
+    type_reference(span, &field.typ)
+}
+
+fn type_reference(span: Span, typ: &Type) -> Option<Reference<'_>> {
+    match typ {
+        Type::Vector(typ) => type_reference(Span { start: span.start + 1, ..span }, typ),
+        Type::Option(typ) => type_reference(span, typ),
+        Type::Defined(name) => Some(Reference {
+            name,
+            span: Span { line: span.line, start: span.start, end: span.start + name.len() },
+            declaration: false,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tl_parser::parse_schema;
+
+    const SCHEMA: &str = "\
+type Photo data:bytes = Photo
+type User id:int64 photos:[Photo] = User
+type UserEmpty id:int64 = User
+func get_users user_ids:[int64] = [User]
+record Point x:float y:float
+func get_path = [Point]
+extern common Money
+func get_balance = Money
+";
+
+    #[test]
+    fn references() {
+        let schema = parse_schema(SCHEMA).unwrap();
+
+        let spans = type_references(&schema, "User").iter()
+            .map(|reference| (reference.span.line, reference.span.start, reference.span.end, reference.declaration))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(2, 36, 40, true), (3, 26, 30, true), (4, 35, 39, false)]);
+
+        let spans = type_references(&schema, "Photo").iter()
+            .map(|reference| (reference.span.line, reference.span.start, reference.span.end, reference.declaration))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(1, 24, 29, true), (2, 27, 32, false)]);
+
+        let spans = type_references(&schema, "Point").iter()
+            .map(|reference| (reference.span.line, reference.span.start, reference.span.end, reference.declaration))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(5, 7, 12, true), (6, 17, 22, false)]);
+
+        let schema = parse_schema("error NotFound\nfunc get_user id:int64 = int64 ! NotFound\n").unwrap();
+        let spans = error_references(&schema, &schema.errors[0]).iter()
+            .map(|reference| (reference.span.line, reference.span.start, reference.span.end, reference.declaration))
+            .collect::<Vec<_>>();
+        assert_eq!(spans, vec![(1, 6, 14, true), (2, 33, 41, false)]);
+        assert_eq!(symbol_at(&schema, 2, 35), Some(Symbol::Error(&schema.errors[0])));
+    }
+
+    #[test]
+    fn symbols() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let mut options = Options::default();
+
+        assert_eq!(symbol_at(&schema, 2, 30), Some(Symbol::Enum("Photo")));
+        assert_eq!(symbol_at(&schema, 4, 36), Some(Symbol::Enum("User")));
+        assert_eq!(symbol_at(&schema, 3, 6), Some(Symbol::Type(&schema.types[2])));
+        assert_eq!(symbol_at(&schema, 4, 1), None);
+        assert_eq!(symbol_at(&schema, 5, 8), Some(Symbol::Record(&schema.records[0])));
+        assert_eq!(symbol_at(&schema, 6, 18), Some(Symbol::Record(&schema.records[0])));
+        assert_eq!(symbol_at(&schema, 8, 20), Some(Symbol::Extern(&schema.externs[0])));
+
+        assert_eq!(
+            hover(&schema, Symbol::Function(&schema.functions[0]), &options),
+            format!(
+                "```tl\nfunc get_users user_ids:[int64] = [User]\n```\n\nid: `0x{:08x}` (1904452899)\n\n\
+                Rust: `functions::GetUsers`",
+                1904452899,
+            ),
+        );
+        assert_eq!(
+            hover(&schema, Symbol::Enum("User"), &options),
+            "```tl\ntype User id:int64 photos:[Photo] = User\ntype UserEmpty id:int64 = User\n```\n\n\
+            Rust: `types::User`",
+        );
+        assert_eq!(
+            hover(&schema, Symbol::Record(&schema.records[0]), &options),
+            "```tl\nrecord Point x:float y:float\n```\n\nRust: `types::Point`",
+        );
+        assert_eq!(
+            hover(&schema, Symbol::Extern(&schema.externs[0]), &options),
+            "```tl\nextern common Money\n```\n\nRust: `crate::common::types::Money`",
+        );
+
+        options.extern_modules.insert("common".to_owned(), "common_api".to_owned());
+        assert_eq!(
+            hover(&schema, Symbol::Extern(&schema.externs[0]), &options),
+            "```tl\nextern common Money\n```\n\nRust: `common_api::types::Money`",
+        );
+    }
+}
@@ -0,0 +1,370 @@
+mod analysis;
+
+use analysis::Symbol;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, References, Rename, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse, Diagnostic,
+    DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, OneOf, Position, PublishDiagnosticsParams, Range,
+    ReferenceParams, RenameParams, ServerCapabilities, TextDocumentPositionParams, TextDocumentSyncCapability,
+    TextDocumentSyncKind, TextEdit, Uri, WorkspaceEdit,
+};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tl_generator::Options;
+use tl_parser::{Schema, Span};
+
+struct Document {
+    text: String,
+    schema: Schema,
+    errors: Vec<tl_parser::Error>,
+    // Of the crate the schema belongs to, for generated names.
+    options: Options,
+}
+
+impl Document {
+    fn new(text: String, options: Options) -> Self {
+        let (schema, errors) = tl_parser::parse_schema_recovering(&text);
+        Self { text, schema, errors, options }
+    }
+
+    fn line(&self, line: usize) -> &str {
+        self.text.split('\n').nth(line - 1).unwrap_or_default()
+    }
+
+    // Parser lines are 1-based with byte columns, LSP lines are 0-based with UTF-16 columns.
+    fn offset(&self, position: Position) -> (usize, usize) {
+        let line = position.line as usize + 1;
+        let mut utf16 = 0;
+        let col = self.line(line).char_indices()
+            .find(|(_, c)| {
+                utf16 += c.len_utf16();
+                utf16 > position.character as usize
+            })
+            .map_or(self.line(line).len(), |(idx, _)| idx);
+        (line, col)
+    }
+
+    fn position(&self, line: usize, col: usize) -> Position {
+        let text = self.line(line);
+        let character = text[..col.min(text.len())].encode_utf16().count();
+        Position::new(line as u32 - 1, character as u32)
+    }
+
+    fn range(&self, span: Span) -> Range {
+        Range::new(self.position(span.line, span.start), self.position(span.line, span.end))
+    }
+
+    fn symbol_at(&self, position: Position) -> Option<Symbol<'_>> {
+        let (line, col) = self.offset(position);
+        analysis::symbol_at(&self.schema, line, col)
+    }
+}
+
+struct Server {
+    connection: Connection,
+    documents: HashMap<Uri, Document>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        rename_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = Server { connection, documents: HashMap::new() };
+    server.run()?;
+
+    drop(server);
+    io_threads.join()?;
+    Ok(())
+}
+
+impl Server {
+    fn run(&mut self) -> anyhow::Result<()> {
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    let response = self.handle_request(req);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&self, req: Request) -> Response {
+        let id = req.id.clone();
+        let method = req.method.clone();
+        let result = match method.as_str() {
+            GotoDefinition::METHOD => handle(req, |params| Ok(self.definition(params))),
+            References::METHOD => handle(req, |params| Ok(self.references(params))),
+            HoverRequest::METHOD => handle(req, |params| Ok(self.hover(params))),
+            Completion::METHOD => handle(req, |params| Ok(self.completion(params))),
+            Rename::METHOD => handle(req, |params| self.rename(params)),
+            _ => Err((ErrorCode::MethodNotFound, format!("unknown method: {method}"))),
+        };
+
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err((code, message)) => Response::new_err(id, code as i32, message),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<()> {
+        let uri = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: lsp_types::DidOpenTextDocumentParams = serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                let options = read_options(&uri);
+                self.documents.insert(uri.clone(), Document::new(params.text_document.text, options));
+                uri
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: lsp_types::DidChangeTextDocumentParams = serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    let options = match self.documents.remove(&uri) {
+                        Some(document) => document.options,
+                        None => read_options(&uri),
+                    };
+                    self.documents.insert(uri.clone(), Document::new(change.text, options));
+                }
+                uri
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: lsp_types::DidCloseTextDocumentParams = serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                params.text_document.uri
+            }
+            _ => return Ok(()),
+        };
+
+        self.publish_diagnostics(uri)
+    }
+
+    fn publish_diagnostics(&self, uri: Uri) -> anyhow::Result<()> {
+        let diagnostics = self.documents.get(&uri)
+            .map(|document| {
+                document.errors.iter()
+                    .map(|err| Diagnostic {
+                        range: document.range(err.span()),
+                        severity: Some(DiagnosticSeverity::ERROR),
+                        source: Some("tl".to_owned()),
+                        message: match err.help() {
+                            Some(help) => format!("{}\nhelp: {help}", err.message()),
+                            None => err.message(),
+                        },
+                        ..Default::default()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let params = PublishDiagnosticsParams { uri, diagnostics, version: None };
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_owned(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    fn document(&self, params: &TextDocumentPositionParams) -> Option<&Document> {
+        self.documents.get(&params.text_document.uri)
+    }
+
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let params = params.text_document_position_params;
+        let document = self.document(&params)?;
+        let uri = &params.text_document.uri;
+
+        let spans = match document.symbol_at(params.position)? {
+            Symbol::Enum(name) => analysis::constructors(&document.schema, name).iter()
+                .map(|def| def.core.span)
+                .collect(),
+            Symbol::Extern(def) => vec![def.span],
+            Symbol::Record(def) => vec![def.core.span],
+            Symbol::Type(def) => vec![def.core.span],
+            Symbol::Error(def) => vec![def.core.span],
+            Symbol::Function(def) => vec![def.core.span],
+        };
+
+        let locations = spans.into_iter()
+            .map(|span| Location::new(uri.clone(), document.range(span)))
+            .collect();
+        Some(GotoDefinitionResponse::Array(locations))
+    }
+
+    fn references(&self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let position = params.text_document_position;
+        let document = self.document(&position)?;
+        let uri = &position.text_document.uri;
+
+        let symbol = document.symbol_at(position.position)?;
+        let spans = match analysis::symbol_references(&document.schema, symbol) {
+            Some(references) => references.into_iter()
+                .filter(|reference| params.context.include_declaration || !reference.declaration)
+                .map(|reference| reference.span)
+                .collect(),
+            None if params.context.include_declaration => symbol.span().into_iter().collect(),
+            None => Vec::new(),
+        };
+
+        let locations = spans.into_iter()
+            .map(|span| Location::new(uri.clone(), document.range(span)))
+            .collect();
+        Some(locations)
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let params = params.text_document_position_params;
+        let document = self.document(&params)?;
+        let symbol = document.symbol_at(params.position)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: analysis::hover(&document.schema, symbol, &document.options),
+            }),
+            range: None,
+        })
+    }
+
+    fn completion(&self, params: CompletionParams) -> Option<CompletionResponse> {
+        let document = self.document(&params.text_document_position)?;
+
+        let builtins = tl_parser::BUILTIN_TYPES.into_iter().chain([tl_parser::VOID_TYPE]).map(|name| CompletionItem {
+            label: name.to_string(),
+            kind: Some(CompletionItemKind::KEYWORD),
+            ..Default::default()
+        });
+        let enums = analysis::enums(&document.schema).into_iter().map(|name| CompletionItem {
+            label: name.to_owned(),
+            kind: Some(CompletionItemKind::ENUM),
+            detail: Some(tl_generator::type_path(name)),
+            ..Default::default()
+        });
+
+        let records = document.schema.records.iter().map(|def| CompletionItem {
+            label: def.core.name.clone(),
+            kind: Some(CompletionItemKind::STRUCT),
+            detail: Some(tl_generator::type_path(&def.core.name)),
+            ..Default::default()
+        });
+
+        let externs = document.schema.externs.iter().map(|def| CompletionItem {
+            label: def.name.clone(),
+            kind: Some(CompletionItemKind::REFERENCE),
+            detail: Some(format!("extern {}", def.schema)),
+            ..Default::default()
+        });
+
+        Some(CompletionResponse::Array(builtins.chain(enums).chain(records).chain(externs).collect()))
+    }
+
+    fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>, (ErrorCode, String)> {
+        let position = params.text_document_position;
+        let Some(document) = self.document(&position) else {
+            return Ok(None);
+        };
+        let Some(symbol) = document.symbol_at(position.position) else {
+            return Ok(None);
+        };
+
+        if !tl_parser::is_valid_name(&params.new_name) {
+            return Err((ErrorCode::InvalidParams, format!("invalid name: {}", params.new_name)));
+        }
+
+        let spans = match analysis::symbol_references(&document.schema, symbol) {
+            Some(references) => references.into_iter()
+                .map(|reference| reference.span)
+                .collect(),
+            None => symbol.span().into_iter().collect::<Vec<_>>(),
+        };
+
+        let edits = spans.into_iter()
+            .map(|span| TextEdit::new(document.range(span), params.new_name.clone()))
+            .collect();
+        Ok(Some(WorkspaceEdit::new(HashMap::from([(position.text_document.uri, edits)]))))
+    }
+}
+
+// Options of `tl.toml` in the crate containing the schema, the defaults for
+// schemas outside of a crate.
+fn read_options(uri: &Uri) -> Options {
+    let path = match uri.scheme() {
+        Some(scheme) if scheme.as_str() == "file" => uri.path().as_estr().decode().into_string().ok(),
+        _ => None,
+    };
+    let Some(path) = path.map(|path| PathBuf::from(path.into_owned())) else {
+        return Options::default();
+    };
+
+    let Some(dir) = path.ancestors().skip(1).find(|dir| dir.join("Cargo.toml").exists()) else {
+        return Options::default();
+    };
+    tl_build::read_options(dir).unwrap_or_else(|err| {
+        eprintln!("tl-lsp: {}: {err}", dir.display());
+        Options::default()
+    })
+}
+
+fn handle<P, R>(
+    req: Request,
+    f: impl FnOnce(P) -> Result<R, (ErrorCode, String)>,
+) -> Result<serde_json::Value, (ErrorCode, String)>
+where
+    P: DeserializeOwned,
+    R: Serialize,
+{
+    let params = serde_json::from_value(req.params)
+        .map_err(|err| (ErrorCode::InvalidParams, err.to_string()))?;
+    let result = f(params)?;
+    Ok(serde_json::to_value(result).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        // `é` is 2 bytes and 1 UTF-16 unit, `😀` is 4 bytes and 2 units.
+        let document = Document::new("type User id:int64 = User\n# é😀 x\n".to_owned(), Options::default());
+
+        assert_eq!(document.offset(Position::new(0, 5)), (1, 5));
+        assert_eq!(document.offset(Position::new(1, 2)), (2, 2));
+        assert_eq!(document.offset(Position::new(1, 3)), (2, 4));
+        assert_eq!(document.offset(Position::new(1, 6)), (2, 9));
+        assert_eq!(document.offset(Position::new(1, 7)), (2, 10));
+        // Inside the surrogate pair of `😀`.
+        assert_eq!(document.offset(Position::new(1, 4)), (2, 4));
+        assert_eq!(document.offset(Position::new(1, 100)), (2, 10));
+
+        assert_eq!(document.position(2, 4), Position::new(1, 3));
+        assert_eq!(document.position(2, 8), Position::new(1, 5));
+        assert_eq!(document.position(2, 10), Position::new(1, 7));
+        assert_eq!(document.position(2, 100), Position::new(1, 7));
+        assert_eq!(
+            document.range(Span { line: 2, start: 2, end: 8 }),
+            Range::new(Position::new(1, 2), Position::new(1, 5)),
+        );
+    }
+}
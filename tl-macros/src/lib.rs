@@ -0,0 +1,163 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, quote_spanned, ToTokens};
+use std::path::Path;
+use std::{env, fs};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{braced, bracketed, LitStr, Token};
+use tl_generator::{Options, Role};
+
+// Generates the module of a schema in place, the path is relative to the
+// crate root: `tl_schema!("schemas/api.tl")`. `runtime = path`,
+// `role = client | server`, `extern_modules = { schema = path }` and
+// `functions = ["get_user*"]` set the generator options of the same name.
+#[proc_macro]
+pub fn tl_schema(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = syn::parse_macro_input!(input as Input);
+    expand(&input, &env::var("CARGO_MANIFEST_DIR").unwrap_or_default()).into()
+}
+
+struct Input {
+    path: LitStr,
+    options: Options,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let path = input.parse()?;
+        let mut options = Options::default();
+
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            let key = input.parse::<Ident>()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "runtime" => options.runtime = input.parse::<syn::Path>()?.to_token_stream().to_string(),
+                "role" => {
+                    let role = input.parse::<Ident>()?;
+                    options.role = match role.to_string().as_str() {
+                        "client" => Role::Client,
+                        "server" => Role::Server,
+                        "both" => Role::Both,
+                        _ => return Err(syn::Error::new(role.span(), "expected `client`, `server` or `both`")),
+                    };
+                }
+                "extern_modules" => {
+                    let content;
+                    braced!(content in input);
+                    for module in Punctuated::<ExternModule, Token![,]>::parse_terminated(&content)? {
+                        options.extern_modules.insert(module.schema.to_string(), module.path);
+                    }
+                }
+                "functions" => {
+                    let content;
+                    bracketed!(content in input);
+                    let functions = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+                    options.functions = Some(functions.iter().map(LitStr::value).collect());
+                }
+                _ => return Err(syn::Error::new(
+                    key.span(),
+                    "expected `runtime`, `role`, `extern_modules` or `functions`",
+                )),
+            }
+        }
+
+        Ok(Self { path, options })
+    }
+}
+
+// `schema = path` in `extern_modules`.
+struct ExternModule {
+    schema: Ident,
+    path: String,
+}
+
+impl Parse for ExternModule {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let schema = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let path = input.parse::<syn::Path>()?.to_token_stream().to_string();
+        Ok(Self { schema, path })
+    }
+}
+
+fn expand(input: &Input, manifest_dir: &str) -> TokenStream {
+    let span = input.path.span();
+    let name = input.path.value();
+    let path = Path::new(manifest_dir).join(&name);
+
+    let source = match fs::read_to_string(&path) {
+        Ok(source) => source,
+        Err(err) => {
+            let message = format!("failed to read schema {}: {err}", path.display());
+            return quote_spanned!(span=> compile_error!(#message););
+        }
+    };
+    // Including the file makes the compiler expand the macro again when it changes.
+    let path = path.to_string_lossy();
+    let track = quote!(const _: &[u8] = include_bytes!(#path););
+
+    // Spans can't point into the schema, the rendered diagnostics carry its
+    // lines instead.
+    let (schema, errors) = tl_parser::parse_schema_recovering(&source);
+    if !errors.is_empty() {
+        let errors = errors.iter().map(|err| {
+            let message = tl_parser::diagnostic::render(err, &source, &name, false);
+            let message = message.strip_prefix("error: ").unwrap_or(&message);
+            quote_spanned!(span=> compile_error!(#message);)
+        });
+        return quote!(#track #(#errors)*);
+    }
+
+    match tl_generator::generate_tokens(&schema, &input.options) {
+        Ok(code) => quote!(#track #code),
+        Err(err) => {
+            let message = err.to_string();
+            quote_spanned!(span=> #track compile_error!(#message);)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors() {
+        let dir = env::temp_dir().join(format!("tl-macros-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("api.tl"), "type User id:int64 = User\nfunc get_user id:int65 = User\n").unwrap();
+        let expand = |input| expand(&syn::parse_str(input).unwrap(), dir.to_str().unwrap()).to_string();
+
+        let code = expand(r#""api.tl""#);
+        assert!(code.contains("include_bytes"));
+        assert!(code.contains("compile_error"));
+        assert!(code.contains("api.tl:2:"));
+
+        fs::write(dir.join("api.tl"), "type User id:int64 = User\nfunc get_user id:int64 = User\n").unwrap();
+        let code = expand(r#""api.tl", runtime = tl_types, role = client"#);
+        assert!(!code.contains("compile_error"));
+        assert!(code.contains("impl tl_types :: Serialize for GetUser"));
+        assert!(!code.contains("impl tl_types :: Deserialize for GetUser"));
+
+        fs::write(dir.join("api.tl"), "\
+extern common Money
+type User id:int64 balance:Money = User
+func get_user id:int64 = User
+func send_money to:int64 = User
+").unwrap();
+        let code = expand(r#""api.tl", extern_modules = { common = ::common }, functions = ["get_*"]"#);
+        assert!(!code.contains("compile_error"));
+        assert!(code.contains(":: common :: types :: Money"));
+        assert!(code.contains("GetUser"));
+        assert!(!code.contains("SendMoney"));
+
+        let code = expand(r#""missing.tl""#);
+        assert!(code.contains("failed to read schema"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,33 @@
+use tl_types::{Call, Deserialize, Serialize};
+
+mod common {
+    tl_macros::tl_schema!("tests/schemas/common.tl", runtime = tl_types);
+}
+
+mod api {
+    tl_macros::tl_schema!(
+        "tests/schemas/api.tl",
+        runtime = tl_types,
+        extern_modules = { common = crate::common },
+        functions = ["get_*"],
+    );
+}
+
+#[test]
+fn round_trip() {
+    let user = api::types::User::User {
+        id: 1,
+        name: "Alice".to_owned(),
+        balance: common::types::Money::Money { amount: 100, currency: "EUR".to_owned() },
+    };
+    assert_eq!(api::types::User::from_bytes(&user.to_bytes()).unwrap(), user);
+
+    let ok = api::types::Result::Ok { values: api::types::Vec { items: vec![1, 2] }, text: None };
+    assert_eq!(api::types::Result::from_bytes(&ok.to_bytes()).unwrap(), ok);
+
+    let function = api::functions::GetUser { id: 1 };
+    assert_eq!(api::Function::from_bytes(&function.to_bytes()).unwrap(), api::Function::GetUser(function));
+
+    let result: Result<<api::functions::GetUser as Call>::Return, api::Error> = Err(api::Error::NotFound { id: 1 });
+    assert_eq!(Result::<api::types::User, api::Error>::from_bytes(&result.to_bytes()).unwrap(), result);
+}
@@ -0,0 +1,511 @@
+use crate::diagnostic::suggest;
+use crate::{
+    check_default, check_listed_error, check_id_collisions, check_reserved, check_type, compute_id, enum_defined, error_defined,
+    extern_defined, field_defined, function_defined, group_defined, is_valid_feature, record_defined, type_defined,
+    validate_name,
+    Constant, DefinitionCore, Error, ErrorDefinition, Extern, Field, FunctionDefinition, GroupDefinition, Options,
+    RecordDefinition, Reserved, Schema, Scope, Span, Type, TypeDefinition, Value,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Definition {
+    name: String,
+    id: Option<u32>,
+    members: Vec<Member>,
+    extensions: Option<Vec<Member>>,
+    errors: Vec<String>,
+    feature: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Member {
+    Field(String, Type, Option<String>),
+    Group(String),
+}
+
+impl Definition {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            id: None,
+            members: Vec::new(),
+            extensions: None,
+            errors: Vec::new(),
+            feature: None,
+        }
+    }
+
+    pub fn id(mut self, id: u32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    pub fn field(mut self, name: impl Into<String>, typ: Type) -> Self {
+        self.members.push(Member::Field(name.into(), typ, None));
+        self
+    }
+
+    // A field documented with the constant it usually holds.
+    pub fn field_with_default(mut self, name: impl Into<String>, typ: Type, constant: impl Into<String>) -> Self {
+        self.members.push(Member::Field(name.into(), typ, Some(constant.into())));
+        self
+    }
+
+    pub fn group(mut self, name: impl Into<String>) -> Self {
+        self.members.push(Member::Group(name.into()));
+        self
+    }
+
+    // Starts the extension block even if there are no extension fields yet.
+    pub fn extensible(mut self) -> Self {
+        self.extensions.get_or_insert_with(Vec::new);
+        self
+    }
+
+    pub fn extension(mut self, name: impl Into<String>, typ: Type) -> Self {
+        self.extensions.get_or_insert_with(Vec::new).push(Member::Field(name.into(), typ, None));
+        self
+    }
+
+    // An error a function returns, listed after `!` when printed.
+    pub fn error(mut self, name: impl Into<String>) -> Self {
+        self.errors.push(name.into());
+        self
+    }
+
+    pub fn feature(mut self, name: impl Into<String>) -> Self {
+        self.feature = Some(name.into());
+        self
+    }
+
+    fn fields(&self, span: Span, scope: Scope<'_>) -> Result<Vec<Field>, Error> {
+        validate_name(span, &self.name)?;
+
+        let mut fields = Vec::new();
+        let extensions = self.extensions.iter().flatten().map(|member| (member, true));
+        for (member, extension) in self.members.iter().map(|member| (member, false)).chain(extensions) {
+            match member {
+                Member::Field(name, typ, default) => {
+                    validate_name(span, name)?;
+                    if field_defined(name, &fields) {
+                        return Err(Error::DuplicateField { span, field: name.clone() });
+                    }
+                    check_type(span, name, typ, scope, None)?;
+                    if let Some(default) = default {
+                        check_default(span, name, typ, default, scope)?;
+                    }
+                    let field = Field {
+                        name: name.clone(),
+                        typ: typ.clone(),
+                        span,
+                        group: None,
+                        extension,
+                        default: default.clone(),
+                    };
+                    push_field(&mut fields, field)?;
+                }
+                Member::Group(name) => {
+                    let Some(group) = scope.groups.iter().find(|group| group.name == *name) else {
+                        return Err(Error::UnknownGroup {
+                            span,
+                            name: name.clone(),
+                            suggestion: suggest(name, scope.groups.iter().map(|group| group.name.as_str())),
+                        });
+                    };
+                    for field in &group.fields {
+                        if field_defined(&field.name, &fields) {
+                            return Err(Error::DuplicateField { span, field: field.name.clone() });
+                        }
+                        let field = Field { span, group: Some(name.clone()), extension, ..field.clone() };
+                        push_field(&mut fields, field)?;
+                    }
+                }
+            }
+        }
+        Ok(fields)
+    }
+
+    fn core(&self, span: Span, scope: Scope<'_>) -> Result<DefinitionCore, Error> {
+        if let Some(feature) = &self.feature
+            && !is_valid_feature(feature)
+        {
+            return Err(Error::InvalidAnnotation { span, annotation: format!("@feature(\"{feature}\")") });
+        }
+
+        Ok(DefinitionCore {
+            id: 0,
+            name: self.name.clone(),
+            fields: self.fields(span, Scope { feature: self.feature.as_deref(), ..scope })?,
+            extensible: self.extensions.is_some(),
+            feature: self.feature.clone(),
+            span,
+            name_span: span,
+        })
+    }
+}
+
+// Records and groups have no ids, the parser reads `record Point#1` as an invalid name.
+fn check_no_id(span: Span, definition: &Definition) -> Result<(), Error> {
+    match definition.id {
+        Some(id) => Err(Error::InvalidName { span, name: format!("{}#{id:08x}", definition.name) }),
+        None => Ok(()),
+    }
+}
+
+fn push_field(fields: &mut Vec<Field>, field: Field) -> Result<(), Error> {
+    if field.extension && !matches!(field.typ, Type::Option(_)) {
+        return Err(Error::ExtensionNotOptional { span: field.span, field: field.name });
+    }
+
+    fields.push(field);
+    Ok(())
+}
+
+// Definitions are numbered from 1 in the order they were added, their spans
+// hold the number as the line.
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("definition {position} ({name}): {}", .error.message())]
+pub struct BuildError {
+    pub position: usize,
+    pub name: String,
+    pub error: Error,
+}
+
+// Definitions are validated as they are added, with the same rules as the
+// parser: they can only refer to definitions added before them.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaBuilder {
+    schema: Schema,
+    // Of every added definition, including invalid ones.
+    names: Vec<String>,
+    errors: Vec<BuildError>,
+    options: Options,
+}
+
+impl SchemaBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn options(&mut self, options: Options) -> &mut Self {
+        self.options = options;
+        self
+    }
+
+    pub fn add_constant(&mut self, name: impl Into<String>, typ: Type, value: Value) -> &mut Self {
+        let name = name.into();
+        let span = self.next_span(&name);
+        let result = self.constant(span, name, typ, value)
+            .map(|constant| self.schema.constants.push(constant));
+        self.finish(result)
+    }
+
+    pub fn add_extern(&mut self, schema: impl Into<String>, name: impl Into<String>) -> &mut Self {
+        let name = name.into();
+        let span = self.next_span(&name);
+        let result = self.r#extern(span, schema.into(), name)
+            .map(|def| self.schema.externs.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_group(&mut self, definition: Definition) -> &mut Self {
+        let span = self.next_span(&definition.name);
+        let result = self.group(span, definition)
+            .map(|def| self.schema.groups.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_record(&mut self, definition: Definition) -> &mut Self {
+        let span = self.next_span(&definition.name);
+        let result = self.record(span, definition)
+            .map(|def| self.schema.records.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_type(&mut self, definition: Definition, r#enum: impl Into<String>) -> &mut Self {
+        let span = self.next_span(&definition.name);
+        let result = self.r#type(span, definition, r#enum.into())
+            .map(|def| self.schema.types.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_error(&mut self, definition: Definition) -> &mut Self {
+        let span = self.next_span(&definition.name);
+        let result = self.error(span, definition)
+            .map(|def| self.schema.errors.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_function(&mut self, definition: Definition, ret: Type) -> &mut Self {
+        let span = self.next_span(&definition.name);
+        let result = self.function(span, definition, ret)
+            .map(|def| self.schema.functions.push(def));
+        self.finish(result)
+    }
+
+    pub fn add_reserved(&mut self, reserved: Reserved) -> &mut Self {
+        let span = match &reserved {
+            Reserved::Name(name) => self.next_span(name),
+            Reserved::Id(id) => self.next_span(&format!("0x{id:08x}")),
+        };
+        let result = match &reserved {
+            Reserved::Name(name) => validate_name(span, name),
+            Reserved::Id(_) => Ok(()),
+        };
+        let result = result.map(|_| self.schema.reserved.push(reserved));
+        self.finish(result)
+    }
+
+    // Errors are sorted by the position of their definition.
+    pub fn build(&self) -> Result<Schema, Vec<BuildError>> {
+        let mut diagnostics = Vec::new();
+        check_id_collisions(&self.schema, &self.options, &mut diagnostics);
+        check_reserved(&self.schema, &mut diagnostics);
+
+        let mut errors = self.errors.iter()
+            .cloned()
+            .chain(diagnostics.into_iter().map(|err| self.build_error(err)))
+            .collect::<Vec<_>>();
+        errors.sort_by_key(|err| err.position);
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(self.schema.clone())
+    }
+
+    fn constant(&self, span: Span, name: String, typ: Type, value: Value) -> Result<Constant, Error> {
+        validate_name(span, &name)?;
+        if self.schema.constants.iter().any(|constant| constant.name == name) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        let valid = match (&typ, &value) {
+            (Type::Int32, Value::Int(value)) => i32::try_from(*value).is_ok(),
+            (Type::Int64, Value::Int(_)) | (Type::Bool, Value::Bool(_)) => true,
+            (Type::Float, Value::Float(value)) => value.is_finite(),
+            (Type::String, Value::String(value)) => !value.contains(['"', '\\']),
+            (Type::Int32 | Type::Int64 | Type::Float | Type::Bool | Type::String, _) => false,
+            _ => return Err(Error::InvalidConstantType { span, name, typ: typ.to_string(), suggestion: None }),
+        };
+        if !valid {
+            return Err(Error::InvalidConstantValue { span, name, typ, value: value.to_string() });
+        }
+
+        Ok(Constant { name, typ, value, span, name_span: span })
+    }
+
+    fn r#extern(&self, span: Span, schema: String, name: String) -> Result<Extern, Error> {
+        validate_name(span, &schema)?;
+        validate_name(span, &name)?;
+        if extern_defined(&name, &self.schema.externs)
+            || enum_defined(&name, &self.schema.types)
+            || record_defined(&name, &self.schema.records)
+        {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        Ok(Extern { schema, name, span, name_span: span })
+    }
+
+    fn group(&self, span: Span, definition: Definition) -> Result<GroupDefinition, Error> {
+        if definition.feature.is_some() {
+            return Err(Error::UnexpectedAnnotation { span });
+        }
+        check_no_id(span, &definition)?;
+        if group_defined(&definition.name, &self.schema.groups) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        let fields = definition.fields(span, self.scope())?;
+        Ok(GroupDefinition { name: definition.name, fields, span, name_span: span })
+    }
+
+    fn record(&self, span: Span, definition: Definition) -> Result<RecordDefinition, Error> {
+        check_no_id(span, &definition)?;
+        let core = definition.core(span, self.scope())?;
+        if record_defined(&core.name, &self.schema.records)
+            || enum_defined(&core.name, &self.schema.types)
+            || extern_defined(&core.name, &self.schema.externs)
+        {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        Ok(RecordDefinition { core })
+    }
+
+    fn r#type(&self, span: Span, definition: Definition, r#enum: String) -> Result<TypeDefinition, Error> {
+        let core = definition.core(span, self.scope())?;
+        if type_defined(&core.name, &self.schema.types) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+        validate_name(span, &r#enum)?;
+        if record_defined(&r#enum, &self.schema.records) || extern_defined(&r#enum, &self.schema.externs) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        let mut def = TypeDefinition { core, r#enum, enum_span: span };
+        def.core.id = definition.id.unwrap_or_else(|| compute_id(&def.canonical()));
+        Ok(def)
+    }
+
+    fn error(&self, span: Span, definition: Definition) -> Result<ErrorDefinition, Error> {
+        let core = definition.core(span, self.scope())?;
+        if error_defined(&core.name, &self.schema.errors) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+
+        let mut def = ErrorDefinition { core };
+        def.core.id = definition.id.unwrap_or_else(|| compute_id(&def.canonical()));
+        Ok(def)
+    }
+
+    fn function(&self, span: Span, definition: Definition, ret: Type) -> Result<FunctionDefinition, Error> {
+        let core = definition.core(span, self.scope())?;
+        if function_defined(&core.name, &self.schema.functions) {
+            return Err(Error::DuplicateDefinition { span });
+        }
+        if ret != Type::Void {
+            let scope = Scope { feature: definition.feature.as_deref(), ..self.scope() };
+            check_type(span, "<return>", &ret, scope, None)?;
+        }
+
+        let mut errors = Vec::new();
+        for name in &definition.errors {
+            check_listed_error(span, name, &errors, &self.schema.errors)?;
+            errors.push((name.clone(), span));
+        }
+
+        let mut def = FunctionDefinition { core, ret, ret_span: span, errors };
+        def.core.id = definition.id.unwrap_or_else(|| compute_id(&def.canonical()));
+        Ok(def)
+    }
+
+    fn scope(&self) -> Scope<'_> {
+        Scope {
+            constants: &self.schema.constants,
+            externs: &self.schema.externs,
+            groups: &self.schema.groups,
+            records: &self.schema.records,
+            types: &self.schema.types,
+            feature: None,
+        }
+    }
+
+    fn next_span(&mut self, name: &str) -> Span {
+        self.names.push(name.to_owned());
+        Span { line: self.names.len(), start: 0, end: 0 }
+    }
+
+    fn finish(&mut self, result: Result<(), Error>) -> &mut Self {
+        if let Err(err) = result {
+            self.errors.push(self.build_error(err));
+        }
+        self
+    }
+
+    fn build_error(&self, error: Error) -> BuildError {
+        let position = error.line();
+        BuildError { position, name: self.names[position - 1].clone(), error }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schema;
+
+    #[test]
+    fn build() {
+        let schema = SchemaBuilder::new()
+            .add_constant("MAX_USERS", Type::Int32, Value::Int(100))
+            .add_extern("common", "Money")
+            .add_group(Definition::new("Timestamps").field("created_at", Type::Time))
+            .add_record(Definition::new("Point").field("x", Type::Float).field("y", Type::Float).extensible())
+            .add_type(
+                Definition::new("User").field("id", Type::Int64).group("Timestamps").field("verified", Type::Bool),
+                "User",
+            )
+            .add_type(Definition::new("UserEmpty").id(0x2a).feature("legacy"), "User")
+            .add_error(
+                Definition::new("InvalidUserId")
+                    .field("user_id", Type::Int64)
+                    .extension("reason", Type::Option(Box::new(Type::String))),
+            )
+            .add_function(
+                Definition::new("get_users")
+                    .field("user_ids", Type::Vector(Box::new(Type::Int64)))
+                    .field_with_default("limit", Type::Option(Box::new(Type::Int32)), "MAX_USERS")
+                    .error("InvalidUserId"),
+                Type::Vector(Box::new(Type::Defined("User".to_owned()))),
+            )
+            .add_reserved(Reserved::Name("OldUser".to_owned()))
+            .build()
+            .unwrap();
+
+        let parsed = parse_schema("\
+const MAX_USERS:int32 = 100
+extern common Money
+group Timestamps created_at:time
+record Point x:float y:float |
+type User id:int64 ..Timestamps verified:bool = User
+@feature(\"legacy\") type UserEmpty#0000002a = User
+error InvalidUserId user_id:int64 | reason:string?
+func get_users user_ids:[int64] limit:int32?=MAX_USERS = [User] ! InvalidUserId
+reserved OldUser
+").unwrap();
+
+        // Spans differ, built definitions have no columns.
+        assert_eq!(schema.to_string(), parsed.to_string());
+        let ids = |schema: &Schema| {
+            schema.types.iter().map(|def| def.core.id)
+                .chain(schema.errors.iter().map(|def| def.core.id))
+                .chain(schema.functions.iter().map(|def| def.core.id))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&schema), ids(&parsed));
+    }
+
+    #[test]
+    fn validation() {
+        let user_id = compute_id("type User id:int64 = User");
+        let errors = SchemaBuilder::new()
+            .add_type(Definition::new("User").field("id", Type::Int64), "User")
+            .add_type(Definition::new("UserEmpty").id(user_id), "User")
+            .add_type(Definition::new("Photo").field("owner", Type::Defined("Owner".to_owned())), "Photo")
+            .add_error(Definition::new("NotFound").field("user id", Type::Int64))
+            .add_function(Definition::new("get_user"), Type::Option(Box::new(Type::Bool)))
+            .add_type(Definition::new("User"), "User")
+            .add_constant("LIMIT", Type::Bytes, Value::Int(1))
+            .add_constant("MAX", Type::Int32, Value::Int(1 << 40))
+            .add_record(Definition::new("Point").id(1).field("x", Type::Float))
+            .add_group(Definition::new("Timestamps").id(2).field("created_at", Type::Time))
+            .add_error(Definition::new("Gone"))
+            .add_function(Definition::new("get_users").error("Gon"), Type::Int32)
+            .add_function(Definition::new("get_photos").error("Gone").error("Gone"), Type::Int32)
+            .build()
+            .unwrap_err();
+
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                format!(
+                    "definition 2 (UserEmpty): UserEmpty has id {user_id:08x} which collides with User on line 1, \
+                    set an explicit id like UserEmpty#{:08x}",
+                    user_id + 1,
+                ),
+                "definition 3 (Photo): field owner: invalid type: Owner".to_owned(),
+                "definition 4 (NotFound): invalid name: user id".to_owned(),
+                "definition 5 (get_user): field <return>: invalid type: Option<Bool>".to_owned(),
+                "definition 6 (User): duplicate definition".to_owned(),
+                "definition 7 (LIMIT): constant LIMIT: invalid type: bytes".to_owned(),
+                "definition 8 (MAX): constant MAX: invalid int32 value: 1099511627776".to_owned(),
+                "definition 9 (Point): invalid name: Point#00000001".to_owned(),
+                "definition 10 (Timestamps): invalid name: Timestamps#00000002".to_owned(),
+                "definition 12 (get_users): unknown error: Gon".to_owned(),
+                "definition 13 (get_photos): error Gone is listed twice".to_owned(),
+            ],
+        );
+    }
+}
@@ -0,0 +1,395 @@
+use crate::{DefinitionCore, Field, Reserved, Schema};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+pub enum DefinitionKind {
+    Record,
+    Type,
+    Error,
+    Function,
+}
+
+impl fmt::Display for DefinitionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DefinitionKind::Record => "record",
+            DefinitionKind::Type => "type",
+            DefinitionKind::Error => "error",
+            DefinitionKind::Function => "function",
+        })
+    }
+}
+
+// Retired definitions still break peers that use them, but removing them
+// after reserving is planned, so CI can choose to allow it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "json", derive(serde::Serialize), serde(rename_all = "snake_case"))]
+pub enum Severity {
+    #[default]
+    Compatible,
+    Retired,
+    Breaking,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Compatible => "compatible",
+            Severity::Retired => "retired",
+            Severity::Breaking => "breaking",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+pub struct Change {
+    pub kind: DefinitionKind,
+    pub name: String,
+    #[cfg_attr(feature = "json", serde(flatten))]
+    pub detail: ChangeDetail,
+    pub severity: Severity,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "json", derive(serde::Serialize), serde(tag = "change", rename_all = "snake_case"))]
+pub enum ChangeDetail {
+    Added,
+    Removed,
+    Retired,
+    ReservedReused,
+    IdChanged { old: u32, new: u32 },
+    EnumChanged { old: String, new: String },
+    ReturnTypeChanged { old: String, new: String },
+    FieldAdded { field: String },
+    FieldRemoved { field: String },
+    FieldTypeChanged { field: String, old: String, new: String },
+    FieldsReordered,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} {}: ", self.severity, self.kind, self.name)?;
+
+        match &self.detail {
+            ChangeDetail::Added => write!(f, "added"),
+            ChangeDetail::Removed => write!(f, "removed"),
+            ChangeDetail::Retired => write!(f, "removed and reserved"),
+            ChangeDetail::ReservedReused => write!(f, "added with a name or id reserved in the old schema"),
+            ChangeDetail::IdChanged { old, new } => write!(f, "id changed from {old:08x} to {new:08x}"),
+            ChangeDetail::EnumChanged { old, new } => write!(f, "enum changed from {old} to {new}"),
+            ChangeDetail::ReturnTypeChanged { old, new } => write!(f, "return type changed from {old} to {new}"),
+            ChangeDetail::FieldAdded { field } => write!(f, "field {field} added"),
+            ChangeDetail::FieldRemoved { field } => write!(f, "field {field} removed"),
+            ChangeDetail::FieldTypeChanged { field, old, new } => {
+                write!(f, "field {field}: type changed from {old} to {new}")
+            }
+            ChangeDetail::FieldsReordered => write!(f, "fields reordered"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Report {
+    pub changes: Vec<Change>,
+}
+
+impl Report {
+    // The most severe change.
+    pub fn severity(&self) -> Severity {
+        self.changes.iter()
+            .map(|change| change.severity)
+            .max()
+            .unwrap_or_default()
+    }
+
+    // Retired definitions aren't breaking, `severity` tells them apart from
+    // compatible changes.
+    pub fn is_breaking(&self) -> bool {
+        self.severity() == Severity::Breaking
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> String {
+        #[derive(serde::Serialize)]
+        struct Json<'a> {
+            breaking: bool,
+            severity: Severity,
+            changes: &'a [Change],
+        }
+
+        serde_json::to_string_pretty(&Json {
+            breaking: self.is_breaking(),
+            severity: self.severity(),
+            changes: &self.changes,
+        })
+        .unwrap()
+    }
+}
+
+struct Definition<'a> {
+    kind: DefinitionKind,
+    core: &'a DefinitionCore,
+    result: Option<String>,
+}
+
+pub fn check_compatibility(old: &Schema, new: &Schema) -> Report {
+    let old_definitions = definitions(old);
+    let new_definitions = definitions(new);
+    let mut changes = Vec::new();
+
+    for old_def in &old_definitions {
+        let mut change = |detail: ChangeDetail, breaking: bool| changes.push(Change {
+            kind: old_def.kind,
+            name: old_def.core.name.clone(),
+            detail,
+            severity: if breaking { Severity::Breaking } else { Severity::Compatible },
+        });
+
+        let Some(new_def) = find(&new_definitions, old_def) else {
+            if is_reserved(new, old_def.core) {
+                changes.push(Change {
+                    kind: old_def.kind,
+                    name: old_def.core.name.clone(),
+                    detail: ChangeDetail::Retired,
+                    severity: Severity::Retired,
+                });
+            } else {
+                change(ChangeDetail::Removed, true);
+            }
+            continue;
+        };
+
+        if old_def.result != new_def.result {
+            let old = old_def.result.clone().unwrap_or_default();
+            let new = new_def.result.clone().unwrap_or_default();
+            match old_def.kind {
+                DefinitionKind::Function => change(ChangeDetail::ReturnTypeChanged { old, new }, true),
+                _ => change(ChangeDetail::EnumChanged { old, new }, true),
+            }
+        }
+
+        let old_fields = &old_def.core.fields;
+        let new_fields = &new_def.core.fields;
+        for old_field in old_fields {
+            match new_fields.iter().find(|field| field.name == old_field.name) {
+                Some(new_field) if new_field.typ != old_field.typ => change(
+                    ChangeDetail::FieldTypeChanged {
+                        field: old_field.name.clone(),
+                        old: old_field.typ.to_string(),
+                        new: new_field.typ.to_string(),
+                    },
+                    true,
+                ),
+                Some(_) => {}
+                None => change(ChangeDetail::FieldRemoved { field: old_field.name.clone() }, true),
+            }
+        }
+        let appended = appended_extensions(old_def.core, new_def.core);
+        for new_field in new_fields {
+            if !old_fields.iter().any(|field| field.name == new_field.name) {
+                let breaking = !appended.iter().any(|field| field.name == new_field.name);
+                change(ChangeDetail::FieldAdded { field: new_field.name.clone() }, breaking);
+            }
+        }
+        let same_names = old_fields.len() == new_fields.len()
+            && old_fields.iter().all(|old_field| new_fields.iter().any(|field| field.name == old_field.name));
+        if same_names && old_fields.iter().zip(new_fields).any(|(old_field, new_field)| old_field.name != new_field.name) {
+            change(ChangeDetail::FieldsReordered, true);
+        }
+
+        if old_def.core.id != new_def.core.id {
+            change(ChangeDetail::IdChanged { old: old_def.core.id, new: new_def.core.id }, true);
+        }
+    }
+
+    // Added definitions, including constructors of existing enums, are
+    // compatible: old readers fail on their unknown ids, but everything old
+    // peers send or expect still decodes. Like a new function, a new
+    // constructor must only be sent once the reader has been updated.
+    for new_def in &new_definitions {
+        if find(&old_definitions, new_def).is_some() {
+            continue;
+        }
+
+        let reused = is_reserved(old, new_def.core);
+        changes.push(Change {
+            kind: new_def.kind,
+            name: new_def.core.name.clone(),
+            detail: if reused { ChangeDetail::ReservedReused } else { ChangeDetail::Added },
+            severity: if reused { Severity::Breaking } else { Severity::Compatible },
+        });
+    }
+
+    Report { changes }
+}
+
+fn definitions(schema: &Schema) -> Vec<Definition<'_>> {
+    let records = schema.records.iter().map(|def| Definition {
+        kind: DefinitionKind::Record,
+        core: &def.core,
+        result: None,
+    });
+    let types = schema.types.iter().map(|def| Definition {
+        kind: DefinitionKind::Type,
+        core: &def.core,
+        result: Some(def.r#enum.clone()),
+    });
+    let errors = schema.errors.iter().map(|def| Definition {
+        kind: DefinitionKind::Error,
+        core: &def.core,
+        result: None,
+    });
+    let functions = schema.functions.iter().map(|def| Definition {
+        kind: DefinitionKind::Function,
+        core: &def.core,
+        result: Some(def.ret.to_string()),
+    });
+
+    records.chain(types).chain(errors).chain(functions).collect()
+}
+
+// Extension fields appended after the old ones are skipped by old readers
+// and left out by old writers.
+fn appended_extensions<'a>(old: &DefinitionCore, new: &'a DefinitionCore) -> &'a [Field] {
+    let old_extensions = old.fields.iter().filter(|field| field.extension).collect::<Vec<_>>();
+    let start = new.fields.iter().position(|field| field.extension).unwrap_or(new.fields.len());
+    let new_extensions = &new.fields[start..];
+
+    let kept = old_extensions.len() <= new_extensions.len()
+        && old_extensions.iter().zip(new_extensions).all(|(old, new)| old.name == new.name && old.typ == new.typ);
+    if !old.extensible || !kept {
+        return &[];
+    }
+    &new_extensions[old_extensions.len()..]
+}
+
+fn find<'a, 'b>(definitions: &'a [Definition<'b>], def: &Definition<'_>) -> Option<&'a Definition<'b>> {
+    definitions.iter().find(|other| other.kind == def.kind && other.core.name == def.core.name)
+}
+
+fn is_reserved(schema: &Schema, def: &DefinitionCore) -> bool {
+    schema.reserved.iter().any(|reserved| match reserved {
+        Reserved::Name(name) => *name == def.name,
+        Reserved::Id(id) => *id == def.id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schema;
+
+    fn check(old: &str, new: &str) -> Vec<String> {
+        let report = check_compatibility(&parse_schema(old).unwrap(), &parse_schema(new).unwrap());
+        report.changes.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn changes() {
+        assert_eq!(
+            check(
+                "\
+type User id:int64 rating:float = User
+type UserEmpty id:int64 = User
+type Photo data:bytes = Photo
+error NotFound
+func get_user id:int64 = User
+func get_photo id:int64 = Photo
+",
+                "\
+type User id:int64 rating:int32 = User
+type UserEmpty id:int64 = Account
+type Photo data:bytes = Photo
+error NotFound
+error TooLong max:int32
+func get_user user_id:int64 = User
+func get_photo id:int64 = [Photo]
+",
+            ),
+            vec![
+                "breaking: type User: field rating: type changed from float to int32",
+                "breaking: type User: id changed from 340c1aae to 01365454",
+                "breaking: type UserEmpty: enum changed from User to Account",
+                "breaking: type UserEmpty: id changed from 3b09d573 to 8ae687a2",
+                "breaking: function get_user: field id removed",
+                "breaking: function get_user: field user_id added",
+                "breaking: function get_user: id changed from 54e2bc39 to 2d1e1106",
+                "breaking: function get_photo: return type changed from Photo to [Photo]",
+                "breaking: function get_photo: id changed from b0b0ae26 to 0e7e260f",
+                "compatible: error TooLong: added",
+            ],
+        );
+    }
+
+    #[test]
+    fn reserved() {
+        let old = "type User id:int64 = User\ntype UserEmpty = User\nfunc get_user id:int64 = User";
+        let new = "type User id:int64 = User\nreserved UserEmpty\nfunc get_user id:int64 = User";
+        assert_eq!(check(old, new), vec!["retired: type UserEmpty: removed and reserved"]);
+        let report = check_compatibility(&parse_schema(old).unwrap(), &parse_schema(new).unwrap());
+        assert_eq!(report.severity(), Severity::Retired);
+        assert!(!report.is_breaking());
+        #[cfg(feature = "json")]
+        assert!(report.to_json().contains("\"breaking\": false,\n  \"severity\": \"retired\""));
+        assert_eq!(check(new, old), vec!["breaking: type UserEmpty: added with a name or id reserved in the old schema"]);
+
+        let new = "type User id:int64 = User\nfunc get_user id:int64 = User";
+        assert_eq!(check(old, new), vec!["breaking: type UserEmpty: removed"]);
+    }
+
+    #[test]
+    fn constructors() {
+        let old = "type User id:int64 = User\nfunc get_user id:int64 = User";
+        let new = "type User id:int64 = User\ntype UserDeleted id:int64 = User\nfunc get_user id:int64 = User";
+        let report = check_compatibility(&parse_schema(old).unwrap(), &parse_schema(new).unwrap());
+        assert_eq!(report.changes.iter().map(ToString::to_string).collect::<Vec<_>>(), vec![
+            "compatible: type UserDeleted: added",
+        ]);
+        assert_eq!(report.severity(), Severity::Compatible);
+    }
+
+    #[test]
+    fn records() {
+        assert_eq!(
+            check(
+                "record Point x:float y:float\nrecord Size width:float",
+                "record Point x:float y:float z:float\nrecord Size width:int32\nrecord Line from:Point",
+            ),
+            vec![
+                "breaking: record Point: field z added",
+                "breaking: record Size: field width: type changed from float to int32",
+                "compatible: record Line: added",
+            ],
+        );
+    }
+
+    #[test]
+    fn extensions() {
+        let old = "type User id:int64 | bio:string? = User";
+        assert_eq!(check(old, "type User id:int64 | bio:string? age:int32? = User"), vec![
+            "compatible: type User: field age added",
+        ]);
+        assert_eq!(check(old, "type User id:int64 | age:int32? bio:string? = User"), vec![
+            "breaking: type User: field age added",
+        ]);
+        assert_eq!(check("type User id:int64 = User", "type User id:int64 | bio:string? = User"), vec![
+            "breaking: type User: field bio added",
+            "breaking: type User: id changed from bee679f1 to 1ea52adf",
+        ]);
+        assert_eq!(check("type User id:int64 | = User", "type User id:int64 | bio:string? = User"), vec![
+            "compatible: type User: field bio added",
+        ]);
+    }
+
+    #[test]
+    fn reordered() {
+        let report = check_compatibility(
+            &parse_schema("error TooLong max:int32 len:int32").unwrap(),
+            &parse_schema("error TooLong#2d6a5e21 len:int32 max:int32").unwrap(),
+        );
+        assert!(report.is_breaking());
+        assert_eq!(report.changes[0].detail, ChangeDetail::FieldsReordered);
+    }
+}
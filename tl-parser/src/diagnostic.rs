@@ -0,0 +1,119 @@
+use crate::Error;
+
+const RED: &str = "1;31";
+const BLUE: &str = "1;34";
+const BOLD: &str = "1";
+
+// Renders an error with the offending line and a caret underline, e.g.
+//
+// error: field x: invalid type: Usr
+//  --> api.tl:4:13
+//   |
+// 4 | error Bad x:Usr
+//   |             ^^^
+//   = help: did you mean `User`?
+pub fn render(error: &Error, source: &str, path: &str, color: bool) -> String {
+    let paint = |style: &str, text: &str| if color {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_owned()
+    };
+
+    let span = error.span();
+    let number = span.line.to_string();
+    let gutter = " ".repeat(number.len());
+    let mut rendered = format!("{}{}\n", paint(RED, "error"), paint(BOLD, &format!(": {}", error.message())));
+
+    match source.split('\n').nth(span.line.wrapping_sub(1)) {
+        Some(text) if span.end <= text.len() => {
+            let text = text.trim_end_matches('\r');
+            let column = text[..span.start].chars().count();
+            let width = text[span.start..span.end.min(text.len())].chars().count().max(1);
+
+            rendered.push_str(&format!("{gutter}{} {path}:{}:{}\n", paint(BLUE, "-->"), span.line, column + 1));
+            rendered.push_str(&format!("{gutter} {}\n", paint(BLUE, "|")));
+            rendered.push_str(&format!("{} {text}\n", paint(BLUE, &format!("{number} |"))));
+            rendered.push_str(&format!(
+                "{gutter} {} {}{}\n",
+                paint(BLUE, "|"),
+                " ".repeat(column),
+                paint(RED, &"^".repeat(width)),
+            ));
+        }
+        _ => rendered.push_str(&format!("{gutter}{} {path}:{}\n", paint(BLUE, "-->"), span.line)),
+    }
+
+    if let Some(help) = error.help() {
+        rendered.push_str(&format!("{gutter} {} {help}\n", paint(BLUE, "= help:")));
+    }
+
+    rendered
+}
+
+pub(crate) fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let max_distance = (name.len() / 3).max(1);
+
+    candidates.into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate.to_owned())
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schema;
+
+    #[test]
+    fn suggestions() {
+        assert_eq!(edit_distance("User", "Users"), 1);
+        assert_eq!(edit_distance("errror", "error"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(suggest("Usr", ["Photo", "User", "Users"]), Some("User".to_owned()));
+        assert_eq!(suggest("Message", ["Photo", "User"]), None);
+    }
+
+    #[test]
+    fn rendered() {
+        let source = "type User id:int64 = User\nerror NotFound user:[Usr]\n";
+        let error = parse_schema(source).unwrap_err();
+
+        assert_eq!(render(&error, source, "api.tl", false), "\
+error: field user: invalid type: Usr
+ --> api.tl:2:22
+  |
+2 | error NotFound user:[Usr]
+  |                      ^^^
+  = help: did you mean `User`?
+");
+
+        let source = "errror NotFound\n";
+        let error = parse_schema(source).unwrap_err();
+        assert_eq!(render(&error, source, "api.tl", true), "\
+\x1b[1;31merror\x1b[0m\x1b[1m: invalid definition type\x1b[0m
+ \x1b[1;34m-->\x1b[0m api.tl:1:1
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m errror NotFound
+  \x1b[1;34m|\x1b[0m \x1b[1;31m^^^^^^\x1b[0m
+  \x1b[1;34m= help:\x1b[0m did you mean `error`?
+");
+    }
+}
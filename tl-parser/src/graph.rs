@@ -0,0 +1,342 @@
+use crate::{Schema, Type};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Node<'a> {
+    // An enum, record or extern, the names functions and fields refer to.
+    Type(&'a str),
+    Error(&'a str),
+    Function(&'a str),
+}
+
+// References between the definitions of a schema. Functions and errors are
+// the roots of the types, functions refer to the errors they list after `!`.
+#[derive(Debug, Clone)]
+pub struct Graph<'a> {
+    nodes: Vec<Node<'a>>,
+    edges: HashMap<Node<'a>, Vec<&'a str>>,
+    errors: HashMap<&'a str, Vec<&'a str>>,
+}
+
+impl<'a> Graph<'a> {
+    pub fn new(schema: &'a Schema) -> Self {
+        let mut graph = Self { nodes: Vec::new(), edges: HashMap::new(), errors: HashMap::new() };
+
+        for def in &schema.externs {
+            graph.add(Node::Type(&def.name), []);
+        }
+        for def in &schema.records {
+            graph.add(Node::Type(&def.core.name), def.core.fields.iter().map(|field| &field.typ));
+        }
+        for def in &schema.types {
+            graph.add(Node::Type(&def.r#enum), def.core.fields.iter().map(|field| &field.typ));
+        }
+        for def in &schema.errors {
+            graph.add(Node::Error(&def.core.name), def.core.fields.iter().map(|field| &field.typ));
+        }
+        for def in &schema.functions {
+            let types = def.core.fields.iter().map(|field| &field.typ).chain([&def.ret]);
+            graph.add(Node::Function(&def.core.name), types);
+            let errors = graph.errors.entry(&def.core.name).or_default();
+            for (name, _) in &def.errors {
+                if !errors.contains(&name.as_str()) {
+                    errors.push(name);
+                }
+            }
+        }
+
+        graph
+    }
+
+    // In declaration order, an enum is declared by its first constructor.
+    pub fn nodes(&self) -> &[Node<'a>] {
+        &self.nodes
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.nodes.iter().filter_map(|node| match node {
+            Node::Type(name) => Some(*name),
+            _ => None,
+        })
+    }
+
+    // Types referred to by the fields or the return type of `node`.
+    pub fn dependencies(&self, node: Node<'a>) -> &[&'a str] {
+        self.edges.get(&node).map_or(&[], Vec::as_slice)
+    }
+
+    // Errors `function` lists after `!`.
+    pub fn errors(&self, function: &str) -> &[&'a str] {
+        self.errors.get(function).map_or(&[], Vec::as_slice)
+    }
+
+    // Definitions referring to `name` directly.
+    pub fn users(&self, name: &str) -> Vec<Node<'a>> {
+        self.nodes.iter()
+            .filter(|node| self.dependencies(**node).contains(&name))
+            .copied()
+            .collect()
+    }
+
+    // Functions referring to `name` directly or through other types.
+    pub fn functions_using(&self, name: &str) -> Vec<&'a str> {
+        self.nodes.iter()
+            .filter_map(|node| match node {
+                Node::Function(function) if self.transitive_dependencies([*node]).contains(&name) => Some(*function),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Types reachable from `roots`, in declaration order.
+    pub fn transitive_dependencies(&self, roots: impl IntoIterator<Item = Node<'a>>) -> Vec<&'a str> {
+        let mut reached = HashSet::new();
+        let mut queue = roots.into_iter()
+            .flat_map(|node| self.dependencies(node).iter().copied())
+            .collect::<Vec<_>>();
+
+        while let Some(name) = queue.pop() {
+            if reached.insert(name) {
+                queue.extend(self.dependencies(Node::Type(name)));
+            }
+        }
+
+        self.types().filter(|name| reached.contains(name)).collect()
+    }
+
+    // Types not reachable from any function or error.
+    pub fn unused_types(&self) -> Vec<&'a str> {
+        let roots = self.nodes.iter().filter(|node| !matches!(node, Node::Type(_))).copied();
+        let used = self.transitive_dependencies(roots);
+        self.types().filter(|name| !used.contains(name)).collect()
+    }
+
+    // Errors no function lists. Lists are optional, so without any every
+    // function may return every error and none is unused.
+    pub fn unused_errors(&self) -> Vec<&'a str> {
+        let listed = self.errors.values().flatten().copied().collect::<HashSet<_>>();
+        if listed.is_empty() {
+            return Vec::new();
+        }
+
+        self.nodes.iter()
+            .filter_map(|node| match node {
+                Node::Error(name) if !listed.contains(name) => Some(*name),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Types referring to each other, dependencies come before their users.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<&'a str>> {
+        let mut tarjan = Tarjan {
+            graph: self,
+            index: HashMap::new(),
+            stack: Vec::new(),
+            on_stack: HashSet::new(),
+            components: Vec::new(),
+        };
+        for name in self.types() {
+            if !tarjan.index.contains_key(name) {
+                tarjan.visit(name);
+            }
+        }
+        tarjan.components
+    }
+
+    // Dependencies first, types in a cycle are kept next to each other.
+    pub fn topological_order(&self) -> Vec<&'a str> {
+        self.strongly_connected_components().into_iter().flatten().collect()
+    }
+
+    // Types that contain themselves, directly or through other types.
+    pub fn recursive_types(&self) -> Vec<&'a str> {
+        let recursive = self.strongly_connected_components().into_iter()
+            .filter(|component| match component.as_slice() {
+                [name] => self.dependencies(Node::Type(name)).contains(name),
+                _ => true,
+            })
+            .flatten()
+            .collect::<HashSet<_>>();
+        self.types().filter(|name| recursive.contains(name)).collect()
+    }
+
+    fn add(&mut self, node: Node<'a>, types: impl IntoIterator<Item = &'a Type>) {
+        let edges = match self.edges.get_mut(&node) {
+            Some(edges) => edges,
+            None => {
+                self.nodes.push(node);
+                self.edges.entry(node).or_default()
+            }
+        };
+
+        for typ in types {
+            if let Some(name) = typ.defined()
+                && !edges.contains(&name)
+            {
+                edges.push(name);
+            }
+        }
+    }
+}
+
+struct Tarjan<'g, 'a> {
+    graph: &'g Graph<'a>,
+    // Visit order, types stay on the stack until their component is done.
+    index: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+// A type being visited, with the position of the next dependency to look at.
+struct Frame<'a> {
+    name: &'a str,
+    index: usize,
+    low: usize,
+    next: usize,
+}
+
+impl<'a> Tarjan<'_, 'a> {
+    // Keeps its own stack of frames, so long chains of types can't overflow
+    // the thread's stack.
+    fn visit(&mut self, name: &'a str) {
+        let mut frames = vec![self.enter(name)];
+
+        while let Some(frame) = frames.last_mut() {
+            if let Some(&dependency) = self.graph.dependencies(Node::Type(frame.name)).get(frame.next) {
+                frame.next += 1;
+                match self.index.get(dependency) {
+                    None => frames.push(self.enter(dependency)),
+                    Some(&index) if self.on_stack.contains(dependency) => frame.low = frame.low.min(index),
+                    Some(_) => {}
+                }
+                continue;
+            }
+
+            let Frame { name, index, low, .. } = frames.pop().unwrap();
+            if let Some(parent) = frames.last_mut() {
+                parent.low = parent.low.min(low);
+            }
+            if low == index {
+                let start = self.stack.iter().rposition(|n| *n == name).unwrap_or_default();
+                let component = self.stack.split_off(start);
+                for name in &component {
+                    self.on_stack.remove(name);
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn enter(&mut self, name: &'a str) -> Frame<'a> {
+        let index = self.index.len();
+        self.index.insert(name, index);
+        self.stack.push(name);
+        self.on_stack.insert(name);
+        Frame { name, index, low: index, next: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_schema, DefinitionCore, Field, Span, TypeDefinition};
+
+    const SCHEMA: &str = "\
+extern common Money
+record Point x:float y:float
+type Shape points:[Point] = Shape
+type Leaf value:int32 = Tree
+type Node value:int32 children:[Tree] = Tree
+type Zero = Even
+type Odd next:Even = Odd
+type Even next:Odd = Even
+type Unused = Unused
+error InvalidShape shape:Shape
+func get_tree = Tree
+func get_even = Even
+func get_price shape:Shape = Money
+";
+
+    #[test]
+    fn dependencies() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let graph = Graph::new(&schema);
+
+        assert_eq!(graph.dependencies(Node::Type("Tree")), ["Tree"]);
+        assert_eq!(graph.dependencies(Node::Function("get_price")), ["Shape", "Money"]);
+        assert_eq!(graph.users("Shape"), [Node::Error("InvalidShape"), Node::Function("get_price")]);
+        assert_eq!(graph.functions_using("Point"), ["get_price"]);
+        assert_eq!(graph.transitive_dependencies([Node::Function("get_price")]), ["Money", "Point", "Shape"]);
+        assert_eq!(graph.unused_types(), ["Unused"]);
+    }
+
+    #[test]
+    fn errors() {
+        let schema = parse_schema("\
+error NotFound
+error InvalidId id:int64
+error RateLimited
+func get_user id:int64 = int64 ! NotFound InvalidId
+func get_users = [int64] ! NotFound
+").unwrap();
+        let graph = Graph::new(&schema);
+
+        assert_eq!(graph.errors("get_user"), ["NotFound", "InvalidId"]);
+        assert_eq!(graph.errors("get_users"), ["NotFound"]);
+        assert_eq!(graph.unused_errors(), ["RateLimited"]);
+
+        let schema = parse_schema(SCHEMA).unwrap();
+        assert!(Graph::new(&schema).unused_errors().is_empty());
+    }
+
+    #[test]
+    fn components() {
+        let schema = parse_schema(SCHEMA).unwrap();
+        let graph = Graph::new(&schema);
+
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![vec!["Money"], vec!["Point"], vec!["Shape"], vec!["Tree"], vec!["Even", "Odd"], vec!["Unused"]],
+        );
+        assert_eq!(graph.recursive_types(), ["Tree", "Even", "Odd"]);
+
+        let order = graph.topological_order();
+        let position = |name| order.iter().position(|n| *n == name).unwrap();
+        assert!(position("Point") < position("Shape"));
+        assert!(position("Odd") < position("Unused"));
+    }
+
+    #[test]
+    fn long_cycle() {
+        let count = 100_000;
+        let types = (0..count)
+            .map(|i| {
+                let field = Field {
+                    name: "next".to_owned(),
+                    typ: Type::Defined(format!("T{}", (i + 1) % count)),
+                    span: Span::default(),
+                    group: None,
+                    extension: false,
+                    default: None,
+                };
+                let core = DefinitionCore {
+                    id: i as u32,
+                    name: format!("T{i}"),
+                    fields: vec![field],
+                    extensible: false,
+                    feature: None,
+                    span: Span::default(),
+                    name_span: Span::default(),
+                };
+                TypeDefinition { core, r#enum: format!("T{i}"), enum_span: Span::default() }
+            })
+            .collect();
+        let schema = Schema { types, ..Schema::default() };
+
+        let components = Graph::new(&schema).strongly_connected_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].len(), count);
+    }
+}
@@ -0,0 +1,357 @@
+use serde::{Deserialize, Serialize};
+
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schema {
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constants: Vec<Constant>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub externs: Vec<Extern>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Group>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub records: Vec<Record>,
+    pub enums: Vec<Enum>,
+    pub types: Vec<TypeDefinition>,
+    pub errors: Vec<ErrorDefinition>,
+    pub functions: Vec<FunctionDefinition>,
+    pub reserved: Vec<Reserved>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Constant {
+    pub name: String,
+    pub r#type: Type,
+    pub value: Value,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Extern {
+    pub schema: String,
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Record {
+    pub name: String,
+    pub canonical: String,
+    pub fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extensible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Enum {
+    pub name: String,
+    pub constructors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TypeDefinition {
+    #[serde(flatten)]
+    pub core: DefinitionCore,
+    pub r#enum: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDefinition {
+    #[serde(flatten)]
+    pub core: DefinitionCore,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    #[serde(flatten)]
+    pub core: DefinitionCore,
+    pub ret: Type,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DefinitionCore {
+    pub id: u32,
+    pub name: String,
+    pub canonical: String,
+    pub fields: Vec<Field>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extensible: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feature: Option<String>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Field {
+    pub name: String,
+    pub r#type: Type,
+    pub span: Span,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub extension: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Type {
+    Int32,
+    Int64,
+    Float,
+    Bool,
+    String,
+    Bytes,
+    Time,
+    Vector { of: Box<Type> },
+    Option { of: Box<Type> },
+    Defined { name: String },
+    Void,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reserved {
+    Name(String),
+    Id(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+pub fn to_json(schema: &crate::Schema) -> String {
+    serde_json::to_string_pretty(&Schema::from(schema)).unwrap()
+}
+
+impl From<&crate::Schema> for Schema {
+    fn from(schema: &crate::Schema) -> Self {
+        let mut enums: Vec<Enum> = Vec::new();
+        for def in &schema.types {
+            match enums.iter_mut().find(|e| e.name == def.r#enum) {
+                Some(e) => e.constructors.push(def.core.name.clone()),
+                None => enums.push(Enum {
+                    name: def.r#enum.clone(),
+                    constructors: vec![def.core.name.clone()],
+                }),
+            }
+        }
+
+        Self {
+            version: VERSION,
+            constants: schema.constants.iter()
+                .map(|constant| Constant {
+                    name: constant.name.clone(),
+                    r#type: Type::from(&constant.typ),
+                    value: match &constant.value {
+                        crate::Value::Int(value) => Value::Int(*value),
+                        crate::Value::Float(value) => Value::Float(*value),
+                        crate::Value::Bool(value) => Value::Bool(*value),
+                        crate::Value::String(value) => Value::String(value.clone()),
+                    },
+                    span: Span::from(constant.span),
+                })
+                .collect(),
+            externs: schema.externs.iter()
+                .map(|def| Extern {
+                    schema: def.schema.clone(),
+                    name: def.name.clone(),
+                    span: Span::from(def.span),
+                })
+                .collect(),
+            groups: schema.groups.iter()
+                .map(|def| Group {
+                    name: def.name.clone(),
+                    fields: def.fields.iter().map(Field::from).collect(),
+                    span: Span::from(def.span),
+                })
+                .collect(),
+            records: schema.records.iter()
+                .map(|def| Record {
+                    name: def.core.name.clone(),
+                    canonical: def.canonical(),
+                    fields: def.core.fields.iter().map(Field::from).collect(),
+                    extensible: def.core.extensible,
+                    feature: def.core.feature.clone(),
+                    span: Span::from(def.core.span),
+                })
+                .collect(),
+            enums,
+            types: schema.types.iter()
+                .map(|def| TypeDefinition {
+                    core: DefinitionCore::new(&def.core, def.canonical()),
+                    r#enum: def.r#enum.clone(),
+                })
+                .collect(),
+            errors: schema.errors.iter()
+                .map(|def| ErrorDefinition {
+                    core: DefinitionCore::new(&def.core, def.canonical()),
+                })
+                .collect(),
+            functions: schema.functions.iter()
+                .map(|def| FunctionDefinition {
+                    core: DefinitionCore::new(&def.core, def.canonical()),
+                    ret: Type::from(&def.ret),
+                    errors: def.errors.iter().map(|(name, _)| name.clone()).collect(),
+                })
+                .collect(),
+            reserved: schema.reserved.iter()
+                .map(|reserved| match reserved {
+                    crate::Reserved::Name(name) => Reserved::Name(name.clone()),
+                    crate::Reserved::Id(id) => Reserved::Id(*id),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl DefinitionCore {
+    fn new(core: &crate::DefinitionCore, canonical: String) -> Self {
+        Self {
+            id: core.id,
+            name: core.name.clone(),
+            canonical,
+            fields: core.fields.iter().map(Field::from).collect(),
+            extensible: core.extensible,
+            feature: core.feature.clone(),
+            span: Span::from(core.span),
+        }
+    }
+}
+
+impl From<&crate::Field> for Field {
+    fn from(field: &crate::Field) -> Self {
+        Self {
+            name: field.name.clone(),
+            r#type: Type::from(&field.typ),
+            span: Span::from(field.span),
+            group: field.group.clone(),
+            extension: field.extension,
+            default: field.default.clone(),
+        }
+    }
+}
+
+impl From<&crate::Type> for Type {
+    fn from(typ: &crate::Type) -> Self {
+        match typ {
+            crate::Type::Int32 => Type::Int32,
+            crate::Type::Int64 => Type::Int64,
+            crate::Type::Float => Type::Float,
+            crate::Type::Bool => Type::Bool,
+            crate::Type::String => Type::String,
+            crate::Type::Bytes => Type::Bytes,
+            crate::Type::Time => Type::Time,
+            crate::Type::Vector(typ) => Type::Vector { of: Box::new(Type::from(typ.as_ref())) },
+            crate::Type::Option(typ) => Type::Option { of: Box::new(Type::from(typ.as_ref())) },
+            crate::Type::Defined(name) => Type::Defined { name: name.clone() },
+            crate::Type::Void => Type::Void,
+        }
+    }
+}
+
+impl From<crate::Span> for Span {
+    fn from(span: crate::Span) -> Self {
+        Self {
+            line: span.line,
+            start: span.start,
+            end: span.end,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schema;
+
+    #[test]
+    fn json() {
+        let schema = parse_schema("\
+type User id:int64 = User
+type UserEmpty = User
+reserved OldUser
+
+func get_users user_ids:[int64] = [User]
+").unwrap();
+
+        let json = serde_json::to_value(Schema::from(&schema)).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "version": 1,
+            "enums": [
+                { "name": "User", "constructors": ["User", "UserEmpty"] },
+            ],
+            "types": [
+                {
+                    "id": schema.types[0].core.id,
+                    "name": "User",
+                    "canonical": "type User id:int64 = User",
+                    "fields": [
+                        {
+                            "name": "id",
+                            "type": { "kind": "int64" },
+                            "span": { "line": 1, "start": 10, "end": 18 },
+                        },
+                    ],
+                    "span": { "line": 1, "start": 0, "end": 25 },
+                    "enum": "User",
+                },
+                {
+                    "id": schema.types[1].core.id,
+                    "name": "UserEmpty",
+                    "canonical": "type UserEmpty = User",
+                    "fields": [],
+                    "span": { "line": 2, "start": 0, "end": 21 },
+                    "enum": "User",
+                },
+            ],
+            "errors": [],
+            "functions": [
+                {
+                    "id": schema.functions[0].core.id,
+                    "name": "get_users",
+                    "canonical": "func get_users user_ids:[int64] = [User]",
+                    "fields": [
+                        {
+                            "name": "user_ids",
+                            "type": { "kind": "vector", "of": { "kind": "int64" } },
+                            "span": { "line": 5, "start": 15, "end": 31 },
+                        },
+                    ],
+                    "span": { "line": 5, "start": 0, "end": 40 },
+                    "ret": { "kind": "vector", "of": { "kind": "defined", "name": "User" } },
+                },
+            ],
+            "reserved": [{ "name": "OldUser" }],
+        }));
+
+        assert_eq!(serde_json::from_value::<Schema>(json).unwrap(), Schema::from(&schema));
+    }
+}
@@ -1,71 +1,485 @@
+pub mod builder;
+pub mod compat;
+pub mod diagnostic;
+pub mod graph;
+#[cfg(feature = "json")]
+pub mod ir;
+pub mod lint;
+
 use aws_lc_rs::digest;
+use diagnostic::suggest;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Write};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+pub const META_SCHEMA: &str = include_str!("meta.tl");
+
+pub const BUILTIN_TYPES: [&str; 7] = ["int32", "int64", "float", "bool", "string", "bytes", "time"];
+
+// Only functions can return it, fields can't have it.
+pub const VOID_TYPE: &str = "void";
+
+const DEFINITION_KINDS: [&str; 8] = ["type", "error", "func", "group", "record", "const", "extern", "reserved"];
+
+pub const CONSTANT_TYPES: [&str; 5] = ["int32", "int64", "float", "bool", "string"];
+
+#[derive(Error, Debug, Clone)]
 pub enum Error {
-    #[error("line {line}: definition type is missing")]
-    DefinitionTypeMissing { line: usize },
+    #[error("line {}: definition type is missing", .span.line)]
+    DefinitionTypeMissing { span: Span },
+
+    #[error("line {}: invalid definition type", .span.line)]
+    InvalidDefinitionType { span: Span, suggestion: Option<String> },
+
+    #[error("line {}: definition name is missing", .span.line)]
+    DefinitionNameMissing { span: Span },
+
+    #[error("line {}: duplicate definition", .span.line)]
+    DuplicateDefinition { span: Span },
+
+    #[error("line {}: function type is missing", .span.line)]
+    FunctionTypeMissing { span: Span },
+
+    #[error("line {}: field {field}: name is missing", .span.line)]
+    FieldNameMissing { span: Span, field: usize },
+
+    #[error("line {}: duplicate field: {field}", .span.line)]
+    DuplicateField { span: Span, field: String },
+
+    #[error("line {}: field {field}: type is missing", .span.line)]
+    FieldTypeMissing { span: Span, field: String },
 
-    #[error("line {line}: invalid definition type")]
-    InvalidDefinitionType { line: usize },
+    #[error("line {}: field {field}: invalid type: {typ}", .span.line)]
+    InvalidType { span: Span, field: String, typ: String, suggestion: Option<String> },
 
-    #[error("line {line}: definition name is missing")]
-    DefinitionNameMissing { line: usize },
+    #[error("line {}: field {field}: extension fields must be optional", .span.line)]
+    ExtensionNotOptional { span: Span, field: String },
 
-    #[error("line {line}: duplicate definition")]
-    DuplicateDefinition { line: usize },
+    #[error("line {}: enum is missing", .span.line)]
+    EnumMissing { span: Span },
 
-    #[error("line {line}: function type is missing")]
-    FunctionTypeMissing { line: usize },
+    #[error("line {}: invalid name: {name}", .span.line)]
+    InvalidName { span: Span, name: String },
 
-    #[error("line {line}: field {field}: name is missing")]
-    FieldNameMissing { line: usize, field: usize },
+    #[error("line {}: invalid definition id: {id}", .span.line)]
+    InvalidDefinitionId { span: Span, id: String },
 
-    #[error("line {line}: duplicate field: {field}")]
-    DuplicateField { line: usize, field: String },
+    #[error(
+        "line {}: {name} has id {id:08x} which collides with {other} on line {other_line}, \
+        set an explicit id like {name}#{suggestion:08x}",
+        .span.line,
+    )]
+    IdCollision {
+        span: Span,
+        name: String,
+        other_line: usize,
+        other: String,
+        id: u32,
+        suggestion: u32,
+    },
 
-    #[error("line {line}: field {field}: type is missing")]
-    FieldTypeMissing { line: usize, field: String },
+    #[error("line {}: unknown field group: {name}", .span.line)]
+    UnknownGroup { span: Span, name: String, suggestion: Option<String> },
 
-    #[error("line {line}: field {field}: invalid type: {typ}")]
-    InvalidType { line: usize, field: String, typ: String },
+    #[error("line {}: constant {name}: type is missing", .span.line)]
+    ConstantTypeMissing { span: Span, name: String },
 
-    #[error("line {line}: enum is missing")]
-    EnumMissing { line: usize },
+    #[error("line {}: constant {name}: invalid type: {typ}", .span.line)]
+    InvalidConstantType { span: Span, name: String, typ: String, suggestion: Option<String> },
+
+    #[error("line {}: constant {name}: value is missing", .span.line)]
+    ConstantValueMissing { span: Span, name: String },
+
+    #[error("line {}: constant {name}: invalid {typ} value: {value}", .span.line)]
+    InvalidConstantValue { span: Span, name: String, typ: Type, value: String },
+
+    #[error("line {}: name {name} is reserved", .span.line)]
+    ReservedName { span: Span, name: String },
+
+    #[error("line {}: {name} has reserved id {id:08x}", .span.line)]
+    ReservedId { span: Span, name: String, id: u32 },
+
+    #[error("line {}: invalid annotation: {annotation}", .span.line)]
+    InvalidAnnotation { span: Span, annotation: String },
+
+    #[error("line {}: only records, types, errors and functions can be annotated", .span.line)]
+    UnexpectedAnnotation { span: Span },
+
+    #[error("line {}: {name} requires feature {feature}", .span.line)]
+    FeatureRequired { span: Span, name: String, feature: String },
+
+    #[error("line {}: errors are missing after `!`", .span.line)]
+    ErrorsMissing { span: Span },
+
+    #[error("line {}: unknown error: {name}", .span.line)]
+    UnknownError { span: Span, name: String, suggestion: Option<String> },
+
+    #[error("line {}: error {name} is listed twice", .span.line)]
+    DuplicateError { span: Span, name: String },
+
+    #[error("line {}: field {field}: unknown constant: {name}", .span.line)]
+    UnknownConstant { span: Span, field: String, name: String, suggestion: Option<String> },
+
+    #[error("line {}: field {field}: constant {constant} is {found}, not {expected}", .span.line)]
+    DefaultTypeMismatch { span: Span, field: String, constant: String, expected: Type, found: Type },
 }
 
+impl Error {
+    pub fn span(&self) -> Span {
+        match self {
+            Error::DefinitionTypeMissing { span }
+            | Error::InvalidDefinitionType { span, .. }
+            | Error::DefinitionNameMissing { span }
+            | Error::DuplicateDefinition { span }
+            | Error::FunctionTypeMissing { span }
+            | Error::FieldNameMissing { span, .. }
+            | Error::DuplicateField { span, .. }
+            | Error::FieldTypeMissing { span, .. }
+            | Error::InvalidType { span, .. }
+            | Error::ExtensionNotOptional { span, .. }
+            | Error::EnumMissing { span }
+            | Error::InvalidName { span, .. }
+            | Error::InvalidDefinitionId { span, .. }
+            | Error::IdCollision { span, .. }
+            | Error::UnknownGroup { span, .. }
+            | Error::ConstantTypeMissing { span, .. }
+            | Error::InvalidConstantType { span, .. }
+            | Error::ConstantValueMissing { span, .. }
+            | Error::InvalidConstantValue { span, .. }
+            | Error::ReservedName { span, .. }
+            | Error::ReservedId { span, .. }
+            | Error::InvalidAnnotation { span, .. }
+            | Error::UnexpectedAnnotation { span }
+            | Error::FeatureRequired { span, .. }
+            | Error::ErrorsMissing { span }
+            | Error::UnknownError { span, .. }
+            | Error::DuplicateError { span, .. }
+            | Error::UnknownConstant { span, .. }
+            | Error::DefaultTypeMismatch { span, .. } => *span,
+        }
+    }
+
+    pub fn line(&self) -> usize {
+        self.span().line
+    }
+
+    // The message without the line prefix.
+    pub fn message(&self) -> String {
+        let message = self.to_string();
+        let prefix = format!("line {}: ", self.line());
+        message.strip_prefix(&prefix).unwrap_or(&message).to_owned()
+    }
+
+    pub fn help(&self) -> Option<String> {
+        match self {
+            Error::InvalidDefinitionType { suggestion: Some(suggestion), .. }
+            | Error::InvalidType { suggestion: Some(suggestion), .. }
+            | Error::UnknownGroup { suggestion: Some(suggestion), .. }
+            | Error::UnknownError { suggestion: Some(suggestion), .. }
+            | Error::UnknownConstant { suggestion: Some(suggestion), .. }
+            | Error::InvalidConstantType { suggestion: Some(suggestion), .. } => {
+                Some(format!("did you mean `{suggestion}`?"))
+            }
+            Error::InvalidDefinitionType { .. } => Some(
+                "definitions start with `type`, `error`, `func`, `group`, `record`, `const`, `extern` or `reserved`"
+                    .to_owned(),
+            ),
+            Error::ExtensionNotOptional { field, .. } => {
+                Some(format!("old readers don't send `{field}`, make it optional with `?`"))
+            }
+            Error::InvalidConstantType { .. } => {
+                Some("constants are `int32`, `int64`, `float`, `bool` or `string`".to_owned())
+            }
+            Error::InvalidConstantValue { typ: Type::String, .. } => {
+                Some("strings are written in double quotes and can't contain `\"` or `\\`".to_owned())
+            }
+            Error::InvalidName { .. } => {
+                Some("names start with a letter or `_` and contain only ASCII letters, digits and `_`".to_owned())
+            }
+            Error::InvalidDefinitionId { .. } => Some("ids are 1 to 8 hex digits".to_owned()),
+            Error::ReservedName { .. } | Error::ReservedId { .. } => {
+                Some("reserved names and ids belong to removed definitions and can't be reused".to_owned())
+            }
+            Error::InvalidAnnotation { .. } => Some(
+                "definitions are gated with `@feature(\"name\")`, \
+                feature names contain only ASCII letters, digits, `-` and `_`"
+                    .to_owned(),
+            ),
+            Error::FeatureRequired { feature, .. } => {
+                Some(format!("gate this definition with `@feature(\"{feature}\")` too"))
+            }
+            Error::UnknownError { .. } => Some("errors are defined before the functions that list them".to_owned()),
+            Error::UnknownConstant { .. } => Some("constants are defined before the fields that use them".to_owned()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub schema_wide_id_check: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct Schema {
+    pub constants: Vec<Constant>,
+    pub externs: Vec<Extern>,
+    pub groups: Vec<GroupDefinition>,
+    pub records: Vec<RecordDefinition>,
     pub types: Vec<TypeDefinition>,
     pub errors: Vec<ErrorDefinition>,
     pub functions: Vec<FunctionDefinition>,
+    pub reserved: Vec<Reserved>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constant {
+    pub name: String,
+    pub typ: Type,
+    pub value: Value,
+    pub span: Span,
+    pub name_span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+// The parser rejects NaN and infinite floats.
+impl Eq for Value {}
+
+// A type defined by another schema, generated code refers to it by path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extern {
+    pub schema: String,
+    pub name: String,
+    pub span: Span,
+    pub name_span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupDefinition {
+    pub name: String,
+    pub fields: Vec<Field>,
+    pub span: Span,
+    pub name_span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DefinitionCore {
     pub id: u32,
     pub name: String,
     pub fields: Vec<Field>,
+    // Set by `|`, the fields after it are written in a length-delimited block
+    // and don't change the id.
+    pub extensible: bool,
+    // Set by `@feature("name")`, generated code is only compiled with the cargo feature.
+    pub feature: Option<String>,
+    pub span: Span,
+    pub name_span: Span,
+}
+
+// Records have no id, `core.id` is always 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordDefinition {
+    pub core: DefinitionCore,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TypeDefinition {
     pub core: DefinitionCore,
     pub r#enum: String,
+    pub enum_span: Span,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ErrorDefinition {
     pub core: DefinitionCore,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct FunctionDefinition {
     pub core: DefinitionCore,
     pub ret: Type,
+    pub ret_span: Span,
+    // Listed after `!` with the span of each name. Functions can still fail
+    // with any error, the list only documents them and isn't part of the id.
+    pub errors: Vec<(String, Span)>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Field {
     pub name: String,
     pub typ: Type,
+    pub span: Span,
+    pub group: Option<String>,
+    pub extension: bool,
+    // The constant after `=`, like `max_length:int32=MAX_TEXT_LENGTH`. It
+    // documents the usual value and isn't part of the id or the wire format.
+    pub default: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reserved {
+    Name(String),
+    Id(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Schema {
+    pub fn canonical(&self) -> String {
+        // Externs, groups, records and types may refer to each other, so they
+        // share a section in declaration order.
+        let mut declarations = self.externs.iter().map(|def| (def.span.line, def.to_string()))
+            .chain(self.groups.iter().map(|def| (def.span.line, def.to_string())))
+            .chain(self.records.iter().map(|def| (def.core.span.line, def.to_string())))
+            .chain(self.types.iter().map(|def| (def.core.span.line, def.to_string())))
+            .collect::<Vec<_>>();
+        declarations.sort_by_key(|(line, _)| *line);
+
+        let sections = [
+            self.constants.iter().map(ToString::to_string).collect(),
+            declarations.into_iter().map(|(_, line)| line).collect::<Vec<_>>(),
+            self.errors.iter().map(ToString::to_string).collect(),
+            self.functions.iter().map(ToString::to_string).collect(),
+            self.reserved.iter().map(ToString::to_string).collect(),
+        ];
+
+        let mut canonical = String::new();
+        for section in sections.iter().filter(|section| !section.is_empty()) {
+            if !canonical.is_empty() {
+                canonical.push('\n');
+            }
+            for line in section {
+                canonical.push_str(line);
+                canonical.push('\n');
+            }
+        }
+        canonical
+    }
+
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let digest = digest::digest(&digest::SHA3_256, self.canonical().as_bytes());
+        let mut buf = [0; 32];
+        buf.clone_from_slice(digest.as_ref());
+        buf
+    }
+}
+
+impl fmt::Display for Schema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+impl RecordDefinition {
+    pub fn canonical(&self) -> String {
+        print_definition("record", &self.core, None, false)
+    }
+}
+
+impl TypeDefinition {
+    pub fn canonical(&self) -> String {
+        print_definition("type", &self.core, Some(&self.r#enum), false)
+    }
+}
+
+impl ErrorDefinition {
+    pub fn canonical(&self) -> String {
+        print_definition("error", &self.core, None, false)
+    }
+}
+
+impl FunctionDefinition {
+    pub fn canonical(&self) -> String {
+        print_definition("func", &self.core, Some(&self.ret.to_string()), false)
+    }
+}
+
+impl fmt::Display for Constant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "const {}:{} = {}", self.name, self.typ, self.value)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{value}"),
+            Value::Float(value) => write!(f, "{value:?}"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::String(value) => write!(f, "\"{value}\""),
+        }
+    }
+}
+
+impl fmt::Display for Extern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "extern {} {}", self.schema, self.name)
+    }
+}
+
+impl fmt::Display for GroupDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("group ")?;
+        f.write_str(&self.name)?;
+        f.write_str(&print_fields(&self.fields, true))
+    }
+}
+
+impl fmt::Display for RecordDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&with_feature(&self.core, print_definition("record", &self.core, None, true)))
+    }
+}
+
+impl fmt::Display for TypeDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = print_definition("type", &self.core, Some(&self.r#enum), true);
+        f.write_str(&with_feature(&self.core, with_explicit_id(&self.core, &self.canonical(), line)))
+    }
+}
+
+impl fmt::Display for ErrorDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let line = print_definition("error", &self.core, None, true);
+        f.write_str(&with_feature(&self.core, with_explicit_id(&self.core, &self.canonical(), line)))
+    }
+}
+
+impl fmt::Display for FunctionDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = print_definition("func", &self.core, Some(&self.ret.to_string()), true);
+        if !self.errors.is_empty() {
+            line.push_str(" !");
+            for (name, _) in &self.errors {
+                write!(line, " {name}").unwrap();
+            }
+        }
+        f.write_str(&with_feature(&self.core, with_explicit_id(&self.core, &self.canonical(), line)))
+    }
+}
+
+impl fmt::Display for Reserved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reserved::Name(name) => write!(f, "reserved {name}"),
+            Reserved::Id(id) => write!(f, "reserved 0x{id:08x}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Type {
     Int32,
     Int64,
@@ -77,6 +491,73 @@ pub enum Type {
     Vector(Box<Type>),
     Option(Box<Type>),
     Defined(String),
+    // Only returned by functions.
+    Void,
+}
+
+impl Type {
+    // The enum, record or extern referred to, looking through vectors and options.
+    pub fn defined(&self) -> Option<&str> {
+        match self {
+            Type::Vector(typ) | Type::Option(typ) => typ.defined(),
+            Type::Defined(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Type::Int32 => f.write_str("int32"),
+            Type::Int64 => f.write_str("int64"),
+            Type::Float => f.write_str("float"),
+            Type::Bool => f.write_str("bool"),
+            Type::String => f.write_str("string"),
+            Type::Bytes => f.write_str("bytes"),
+            Type::Time => f.write_str("time"),
+            Type::Vector(typ) => write!(f, "[{typ}]"),
+            Type::Option(typ) => write!(f, "{typ}?"),
+            Type::Defined(name) => f.write_str(name),
+            Type::Void => f.write_str(VOID_TYPE),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Line<'a> {
+    number: usize,
+    text: &'a str,
+    code: &'a str,
+}
+
+impl<'a> Line<'a> {
+    fn new(number: usize, text: &'a str, first: &'a str, last: &'a str) -> Self {
+        let start = offset(text, first);
+        let end = offset(text, last) + last.len();
+        Self { number, text, code: &text[start..end] }
+    }
+
+    fn span(&self, part: &str) -> Span {
+        let start = offset(self.text, part);
+        Span { line: self.number, start, end: start + part.len() }
+    }
+}
+
+// `part` must be a substring of `text`.
+fn offset(text: &str, part: &str) -> usize {
+    part.as_ptr() as usize - text.as_ptr() as usize
+}
+
+#[derive(Clone, Copy)]
+struct Scope<'a> {
+    constants: &'a [Constant],
+    externs: &'a [Extern],
+    groups: &'a [GroupDefinition],
+    records: &'a [RecordDefinition],
+    types: &'a [TypeDefinition],
+    // Of the definition being parsed.
+    feature: Option<&'a str>,
 }
 
 #[derive(Debug)]
@@ -86,144 +567,704 @@ enum OuterType {
 }
 
 pub fn parse_schema(schema: &str) -> Result<Schema, Error> {
+    let (schema, errors) = parse_schema_recovering(schema);
+
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(schema),
+    }
+}
+
+pub fn parse_schema_recovering(schema: &str) -> (Schema, Vec<Error>) {
+    parse_schema_with_options(schema, &Options::default())
+}
+
+pub fn parse_schema_with_options(schema: &str, options: &Options) -> (Schema, Vec<Error>) {
+    let mut constants = Vec::new();
+    let mut externs = Vec::new();
+    let mut groups = Vec::new();
+    let mut records = Vec::new();
     let mut types = Vec::new();
     let mut errors = Vec::new();
     let mut functions = Vec::new();
+    let mut reserved = Vec::new();
+    let mut diagnostics = Vec::new();
 
-    for (idx, def) in schema.split("\n").enumerate() {
-        let line = idx + 1;
-        let id = compute_definition_id(def);
-        let mut def = def.split(" ");
-
-        match def.next() {
-            Some("" | "#") => continue,
-            Some("type") => types.push(parse_type_definition(id, line, def, &types)?),
-            Some("error") => errors.push(parse_error_definition(id, line, def, &types, &errors)?),
-            Some("func") => functions.push(parse_function_definition(id, line, def, &types, &functions)?),
-            Some(_) => return Err(Error::InvalidDefinitionType { line }),
-            None => return Err(Error::DefinitionTypeMissing { line }),
+    for (idx, text) in schema.split("\n").enumerate() {
+        let parts = split_line(text);
+        let (Some(first), Some(last)) = (parts.first(), parts.last()) else {
+            continue;
+        };
+        let line = Line::new(idx + 1, text, first, last);
+        let mut def = parts.into_iter().peekable();
+        let feature = match def.next_if(|part| part.starts_with('@')) {
+            Some(annotation) => match parse_annotation(line, annotation) {
+                Ok(feature) => Some(feature),
+                Err(err) => {
+                    diagnostics.push(err);
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let scope = Scope {
+            constants: &constants,
+            externs: &externs,
+            groups: &groups,
+            records: &records,
+            types: &types,
+            feature,
         };
+
+        if let Some(annotation) = feature
+            && !def.peek().is_some_and(|kind| ["record", "type", "error", "func"].contains(kind))
+        {
+            diagnostics.push(Error::UnexpectedAnnotation { span: line.span(annotation) });
+            continue;
+        }
+
+        let result = match def.next() {
+            Some("extern") => parse_extern(line, def, scope)
+                .map(|items| externs.extend(items)),
+            Some("group") => parse_group_definition(line, def, scope)
+                .map(|def| groups.push(def)),
+            Some("record") => parse_record_definition(line, def, scope)
+                .map(|def| records.push(def)),
+            Some("type") => parse_type_definition(line, def, scope)
+                .map(|def| types.push(def)),
+            Some("error") => parse_error_definition(line, def, scope, &errors)
+                .map(|def| errors.push(def)),
+            Some("func") => parse_function_definition(line, def, scope, &errors, &functions)
+                .map(|def| functions.push(def)),
+            Some("const") => parse_constant(line, def, &constants)
+                .map(|constant| constants.push(constant)),
+            Some("reserved") => parse_reserved(line, def)
+                .map(|items| reserved.extend(items)),
+            Some(kind) => Err(Error::InvalidDefinitionType {
+                span: line.span(kind),
+                suggestion: suggest(kind, DEFINITION_KINDS),
+            }),
+            None => Err(Error::DefinitionTypeMissing { span: line.span(line.code) }),
+        };
+
+        if let Err(err) = result {
+            diagnostics.push(err);
+        }
     }
 
-    Ok(Schema { types, errors, functions })
+    let schema = Schema { constants, externs, groups, records, types, errors, functions, reserved };
+    check_id_collisions(&schema, options, &mut diagnostics);
+    check_reserved(&schema, &mut diagnostics);
+
+    (schema, diagnostics)
 }
 
-fn compute_definition_id(def: &str) -> u32 {
-    let digest = digest::digest(&digest::SHA3_256, def.as_bytes());
+// Splits a line into whitespace separated parts up to a `#` comment. Quoted
+// strings are kept whole, so they can contain spaces and `#`.
+fn split_line(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+
+    for (idx, c) in text.char_indices() {
+        match start {
+            None if c == '#' => return parts,
+            None if !c.is_whitespace() => {
+                start = Some(idx);
+                quoted = c == '"';
+            }
+            Some(_) if c == '"' => quoted = !quoted,
+            Some(from) if c.is_whitespace() && !quoted => {
+                parts.push(&text[from..idx]);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(from) = start {
+        parts.push(&text[from..]);
+    }
+    parts
+}
+
+// The canonical form lists fields of groups one by one and leaves out
+// extension fields, the printed form splices groups back as `..Group`.
+fn print_definition(kind: &str, core: &DefinitionCore, result: Option<&str>, printed: bool) -> String {
+    let (fields, extensions) = core.fields.split_at(
+        core.fields.iter().position(|field| field.extension).unwrap_or(core.fields.len()),
+    );
+
+    let mut line = format!("{kind} {}", core.name);
+    line.push_str(&print_fields(fields, printed));
+    if core.extensible {
+        line.push_str(" |");
+        if printed {
+            line.push_str(&print_fields(extensions, printed));
+        }
+    }
+    if let Some(result) = result {
+        write!(line, " = {result}").unwrap();
+    }
+    line
+}
+
+fn print_fields(fields: &[Field], splice_groups: bool) -> String {
+    let mut line = String::new();
+    let mut group = None;
+    for field in fields {
+        match &field.group {
+            Some(name) if splice_groups => {
+                if group != Some(name) {
+                    write!(line, " ..{name}").unwrap();
+                }
+            }
+            _ => {
+                write!(line, " {}:{}", field.name, field.typ).unwrap();
+                if let Some(default) = &field.default
+                    && splice_groups
+                {
+                    write!(line, "={default}").unwrap();
+                }
+            }
+        }
+        group = field.group.as_ref();
+    }
+    line
+}
+
+// Keeps explicit ids, which differ from the computed ones.
+fn with_explicit_id(core: &DefinitionCore, canonical: &str, mut line: String) -> String {
+    if core.id != compute_id(canonical) {
+        let at = line.find(' ').unwrap() + 1 + core.name.len();
+        line.insert_str(at, &format!("#{:08x}", core.id));
+    }
+    line
+}
+
+// Features don't change the wire format, so they're left out of the id.
+fn with_feature(core: &DefinitionCore, line: String) -> String {
+    match &core.feature {
+        Some(feature) => format!("@feature(\"{feature}\") {line}"),
+        None => line,
+    }
+}
+
+pub fn compute_id(canonical: &str) -> u32 {
+    let digest = digest::digest(&digest::SHA3_256, canonical.as_bytes());
     let mut buf = [0; 4];
     buf.clone_from_slice(&digest.as_ref()[..4]);
     u32::from_le_bytes(buf)
 }
 
+fn check_id_collisions(schema: &Schema, options: &Options, diagnostics: &mut Vec<Error>) {
+    let types = schema.types.iter().map(|def| &def.core);
+    let errors = schema.errors.iter().map(|def| &def.core);
+    let functions = schema.functions.iter().map(|def| &def.core);
+
+    if options.schema_wide_id_check {
+        let mut definitions = types.chain(errors).chain(functions).collect::<Vec<_>>();
+        definitions.sort_by_key(|def| def.span.line);
+        check_id_collisions_in_scope(&definitions, diagnostics);
+        return;
+    }
+
+    let mut enums: Vec<(&str, Vec<&DefinitionCore>)> = Vec::new();
+    for def in &schema.types {
+        match enums.iter_mut().find(|(name, _)| *name == def.r#enum) {
+            Some((_, definitions)) => definitions.push(&def.core),
+            None => enums.push((&def.r#enum, vec![&def.core])),
+        }
+    }
+    for (_, definitions) in enums {
+        check_id_collisions_in_scope(&definitions, diagnostics);
+    }
+    check_id_collisions_in_scope(&errors.collect::<Vec<_>>(), diagnostics);
+    check_id_collisions_in_scope(&functions.collect::<Vec<_>>(), diagnostics);
+}
+
+fn check_id_collisions_in_scope(definitions: &[&DefinitionCore], diagnostics: &mut Vec<Error>) {
+    let ids = definitions.iter()
+        .map(|def| def.id)
+        .collect::<HashSet<_>>();
+    let mut seen = HashMap::new();
+
+    for &def in definitions {
+        let Some(other) = seen.insert(def.id, def) else {
+            continue;
+        };
+        seen.insert(def.id, other);
+
+        let suggestion = (1..)
+            .map(|offset| def.id.wrapping_add(offset))
+            .find(|id| !ids.contains(id))
+            .unwrap();
+
+        diagnostics.push(Error::IdCollision {
+            span: def.name_span,
+            name: def.name.clone(),
+            other_line: other.span.line,
+            other: other.name.clone(),
+            id: def.id,
+            suggestion,
+        });
+    }
+}
+
+fn check_reserved(schema: &Schema, diagnostics: &mut Vec<Error>) {
+    let definitions = schema.types.iter().map(|def| &def.core)
+        .chain(schema.errors.iter().map(|def| &def.core))
+        .chain(schema.functions.iter().map(|def| &def.core));
+
+    for def in &schema.records {
+        if schema.reserved.contains(&Reserved::Name(def.core.name.clone())) {
+            diagnostics.push(Error::ReservedName { span: def.core.name_span, name: def.core.name.clone() });
+        }
+    }
+
+    for def in definitions {
+        for reserved in &schema.reserved {
+            match reserved {
+                Reserved::Name(name) if *name == def.name => diagnostics.push(Error::ReservedName {
+                    span: def.name_span,
+                    name: name.clone(),
+                }),
+                Reserved::Id(id) if *id == def.id => diagnostics.push(Error::ReservedId {
+                    span: def.name_span,
+                    name: def.name.clone(),
+                    id: *id,
+                }),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn parse_constant<'a>(
+    line: Line<'a>,
+    mut def: impl Iterator<Item = &'a str>,
+    constants: &[Constant],
+) -> Result<Constant, Error> {
+    let part = def.next()
+        .ok_or(Error::DefinitionNameMissing { span: line.span(line.code) })?;
+    let (name, typ) = part.split_once(':')
+        .ok_or_else(|| Error::ConstantTypeMissing { span: line.span(part), name: part.to_owned() })?;
+    validate_name(line.span(name), name)?;
+    if constants.iter().any(|constant| constant.name == name) {
+        return Err(Error::DuplicateDefinition { span: line.span(name) });
+    }
+
+    let typ = match typ {
+        "int32" => Type::Int32,
+        "int64" => Type::Int64,
+        "float" => Type::Float,
+        "bool" => Type::Bool,
+        "string" => Type::String,
+        _ => return Err(Error::InvalidConstantType {
+            span: line.span(typ),
+            name: name.to_owned(),
+            typ: typ.to_owned(),
+            suggestion: suggest(typ, CONSTANT_TYPES),
+        }),
+    };
+
+    // String values may contain spaces, so the value is the rest of the line.
+    let value = match (def.next(), def.next()) {
+        (Some("="), Some(value)) => &line.code[offset(line.code, value)..],
+        _ => return Err(Error::ConstantValueMissing { span: line.span(line.code), name: name.to_owned() }),
+    };
+    let parsed = match typ {
+        Type::Int32 => value.parse::<i32>().map(|value| Value::Int(value.into())).ok(),
+        Type::Int64 => value.parse::<i64>().map(Value::Int).ok(),
+        Type::Float => value.parse::<f64>().ok().filter(|value| value.is_finite()).map(Value::Float),
+        Type::Bool => value.parse::<bool>().map(Value::Bool).ok(),
+        _ => value.strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .filter(|value| !value.contains(['"', '\\']))
+            .map(|value| Value::String(value.to_owned())),
+    };
+    let Some(parsed) = parsed else {
+        return Err(Error::InvalidConstantValue {
+            span: line.span(value),
+            name: name.to_owned(),
+            typ,
+            value: value.to_owned(),
+        });
+    };
+
+    Ok(Constant {
+        name: name.to_owned(),
+        typ,
+        value: parsed,
+        span: line.span(line.code),
+        name_span: line.span(name),
+    })
+}
+
+fn parse_extern<'a>(
+    line: Line<'a>,
+    mut def: impl Iterator<Item = &'a str>,
+    scope: Scope<'_>,
+) -> Result<Vec<Extern>, Error> {
+    let schema = def.next()
+        .ok_or(Error::DefinitionNameMissing { span: line.span(line.code) })?;
+    validate_name(line.span(schema), schema)?;
+
+    let mut externs = Vec::new();
+    for name in def {
+        validate_name(line.span(name), name)?;
+        if extern_defined(name, scope.externs)
+            || extern_defined(name, &externs)
+            || enum_defined(name, scope.types)
+            || record_defined(name, scope.records)
+        {
+            return Err(Error::DuplicateDefinition { span: line.span(name) });
+        }
+
+        externs.push(Extern {
+            schema: schema.to_owned(),
+            name: name.to_owned(),
+            span: line.span(line.code),
+            name_span: line.span(name),
+        });
+    }
+
+    if externs.is_empty() {
+        return Err(Error::DefinitionNameMissing { span: line.span(line.code) });
+    }
+    Ok(externs)
+}
+
+fn parse_reserved<'a>(
+    line: Line<'a>,
+    def: impl Iterator<Item = &'a str>,
+) -> Result<Vec<Reserved>, Error> {
+    let mut reserved = Vec::new();
+
+    for part in def {
+        match part.strip_prefix("0x") {
+            Some(id) => reserved.push(Reserved::Id(parse_definition_id(line.span(id), id)?)),
+            None => {
+                validate_name(line.span(part), part)?;
+                reserved.push(Reserved::Name(part.to_owned()));
+            }
+        }
+    }
+
+    if reserved.is_empty() {
+        return Err(Error::DefinitionNameMissing { span: line.span(line.code) });
+    }
+
+    Ok(reserved)
+}
+
+fn parse_group_definition<'a>(
+    line: Line<'a>,
+    mut def: impl Iterator<Item = &'a str>,
+    scope: Scope<'_>,
+) -> Result<GroupDefinition, Error> {
+    let name = def.next()
+        .ok_or(Error::DefinitionNameMissing { span: line.span(line.code) })?;
+    validate_name(line.span(name), name)?;
+
+    if group_defined(name, scope.groups) {
+        return Err(Error::DuplicateDefinition { span: line.span(name) });
+    }
+
+    let (fields, _) = parse_fields(line, &mut def, scope, None, false)?;
+
+    Ok(GroupDefinition {
+        name: name.to_owned(),
+        fields,
+        span: line.span(line.code),
+        name_span: line.span(name),
+    })
+}
+
+fn parse_record_definition<'a>(
+    line: Line<'a>,
+    mut def: impl Iterator<Item = &'a str>,
+    scope: Scope<'_>,
+) -> Result<RecordDefinition, Error> {
+    let name = def.next()
+        .ok_or(Error::DefinitionNameMissing { span: line.span(line.code) })?;
+    validate_name(line.span(name), name)?;
+
+    if record_defined(name, scope.records)
+        || enum_defined(name, scope.types)
+        || extern_defined(name, scope.externs)
+    {
+        return Err(Error::DuplicateDefinition { span: line.span(name) });
+    }
+
+    let (fields, extensible) = parse_fields(line, &mut def, scope, None, true)?;
+
+    let core = DefinitionCore {
+        id: 0,
+        name: name.to_owned(),
+        fields,
+        extensible,
+        feature: scope.feature.map(str::to_owned),
+        span: line.span(line.code),
+        name_span: line.span(name),
+    };
+    Ok(RecordDefinition { core })
+}
+
 fn parse_type_definition<'a>(
-    id: u32,
-    line: usize,
+    line: Line<'a>,
     mut def: impl Iterator<Item = &'a str>,
-    type_definitions: &[TypeDefinition],
+    scope: Scope<'_>,
 ) -> Result<TypeDefinition, Error> {
-    let core = parse_definition_core(id, line, &mut def, type_definitions)?;
+    let (core, id) = parse_definition_core(line, &mut def, scope)?;
 
-    if type_defined(&core.name, type_definitions) {
-        return Err(Error::DuplicateDefinition { line });
+    if type_defined(&core.name, scope.types) {
+        return Err(Error::DuplicateDefinition { span: core.name_span });
     }
 
     let r#enum = def.next()
-        .ok_or(Error::EnumMissing { line })?
-        .to_owned();
+        .ok_or(Error::EnumMissing { span: line.span(line.code) })?;
+    validate_name(line.span(r#enum), r#enum)?;
+    if record_defined(r#enum, scope.records) || extern_defined(r#enum, scope.externs) {
+        return Err(Error::DuplicateDefinition { span: line.span(r#enum) });
+    }
 
-    Ok(TypeDefinition { core, r#enum })
+    let mut def = TypeDefinition { core, r#enum: r#enum.to_owned(), enum_span: line.span(r#enum) };
+    def.core.id = id.unwrap_or_else(|| compute_id(&def.canonical()));
+    Ok(def)
 }
 
 fn parse_error_definition<'a>(
-    id: u32,
-    line: usize,
+    line: Line<'a>,
     mut def: impl Iterator<Item = &'a str>,
-    type_definitions: &[TypeDefinition],
+    scope: Scope<'_>,
     error_definitions: &[ErrorDefinition],
 ) -> Result<ErrorDefinition, Error> {
-    let core = parse_definition_core(id, line, &mut def, type_definitions)?;
+    let (core, id) = parse_definition_core(line, &mut def, scope)?;
 
     if error_defined(&core.name, error_definitions) {
-        return Err(Error::DuplicateDefinition { line });
+        return Err(Error::DuplicateDefinition { span: core.name_span });
     }
 
-    Ok(ErrorDefinition { core })
+    let mut def = ErrorDefinition { core };
+    def.core.id = id.unwrap_or_else(|| compute_id(&def.canonical()));
+    Ok(def)
 }
 
 fn parse_function_definition<'a>(
-    id: u32,
-    line: usize,
+    line: Line<'a>,
     mut def: impl Iterator<Item = &'a str>,
-    type_definitions: &[TypeDefinition],
+    scope: Scope<'_>,
+    error_definitions: &[ErrorDefinition],
     function_definitions: &[FunctionDefinition],
 ) -> Result<FunctionDefinition, Error> {
-    let core = parse_definition_core(id, line, &mut def, type_definitions)?;
+    let (core, id) = parse_definition_core(line, &mut def, scope)?;
 
     if function_defined(&core.name, function_definitions) {
-        return Err(Error::DuplicateDefinition { line });
+        return Err(Error::DuplicateDefinition { span: core.name_span });
     }
 
     let ret = def.next()
-        .ok_or(Error::FunctionTypeMissing { line })?;
-    let ret = parse_type(line, "<return>", ret, type_definitions, None)?;
+        .ok_or(Error::FunctionTypeMissing { span: line.span(line.code) })?;
+    let ret_span = line.span(ret);
+    let ret = match ret {
+        VOID_TYPE => Type::Void,
+        _ => parse_type(line, "<return>", ret, scope, None)?,
+    };
+
+    let mut errors = Vec::new();
+    if let Some(bang) = def.next().filter(|&part| part == "!") {
+        for name in def {
+            check_listed_error(line.span(name), name, &errors, error_definitions)?;
+            errors.push((name.to_owned(), line.span(name)));
+        }
+        if errors.is_empty() {
+            return Err(Error::ErrorsMissing { span: line.span(bang) });
+        }
+    }
 
-    Ok(FunctionDefinition { core, ret })
+    let mut def = FunctionDefinition { core, ret, ret_span, errors };
+    def.core.id = id.unwrap_or_else(|| compute_id(&def.canonical()));
+    Ok(def)
+}
+
+// Listed errors must be defined before the function and listed once.
+fn check_listed_error(
+    span: Span,
+    name: &str,
+    listed: &[(String, Span)],
+    error_definitions: &[ErrorDefinition],
+) -> Result<(), Error> {
+    if !error_defined(name, error_definitions) {
+        return Err(Error::UnknownError {
+            span,
+            name: name.to_owned(),
+            suggestion: suggest(name, error_definitions.iter().map(|def| def.core.name.as_str())),
+        });
+    }
+    if listed.iter().any(|(listed, _)| listed == name) {
+        return Err(Error::DuplicateError { span, name: name.to_owned() });
+    }
+    Ok(())
 }
 
 fn parse_definition_core<'a>(
-    id: u32,
-    line: usize,
+    line: Line<'a>,
     def: &mut impl Iterator<Item = &'a str>,
-    type_definitions: &[TypeDefinition],
-) -> Result<DefinitionCore, Error> {
+    scope: Scope<'_>,
+) -> Result<(DefinitionCore, Option<u32>), Error> {
     let name = def.next()
-        .ok_or(Error::DefinitionNameMissing { line })?
-        .to_owned();
+        .ok_or(Error::DefinitionNameMissing { span: line.span(line.code) })?;
+    let (name, id) = match name.split_once('#') {
+        Some((name, id)) => (name, Some(parse_definition_id(line.span(id), id)?)),
+        None => (name, None),
+    };
+    validate_name(line.span(name), name)?;
 
-    let fields = parse_fields(line, def, type_definitions, "=")?;
+    let (fields, extensible) = parse_fields(line, def, scope, Some("="), true)?;
 
-    Ok(DefinitionCore { id, name, fields })
+    let core = DefinitionCore {
+        id: 0,
+        name: name.to_owned(),
+        fields,
+        extensible,
+        feature: scope.feature.map(str::to_owned),
+        span: line.span(line.code),
+        name_span: line.span(name),
+    };
+    Ok((core, id))
+}
+
+fn parse_annotation<'a>(line: Line<'a>, annotation: &'a str) -> Result<&'a str, Error> {
+    let feature = annotation.strip_prefix("@feature(\"")
+        .and_then(|rest| rest.strip_suffix("\")"))
+        .filter(|feature| is_valid_feature(feature));
+
+    feature.ok_or_else(|| Error::InvalidAnnotation {
+        span: line.span(annotation),
+        annotation: annotation.to_owned(),
+    })
+}
+
+fn is_valid_feature(feature: &str) -> bool {
+    !feature.is_empty() && feature.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn is_valid_name(name: &str) -> bool {
+    name != "_"
+        && name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub(crate) fn validate_name(span: Span, name: &str) -> Result<(), Error> {
+    if !is_valid_name(name) {
+        return Err(Error::InvalidName { span, name: name.to_owned() });
+    }
+
+    Ok(())
+}
+
+fn parse_definition_id(span: Span, id: &str) -> Result<u32, Error> {
+    if id.is_empty() || id.len() > 8 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidDefinitionId { span, id: id.to_owned() });
+    }
+
+    Ok(u32::from_str_radix(id, 16).unwrap())
 }
 
 fn parse_fields<'a>(
-    line: usize,
+    line: Line<'a>,
     def: &mut impl Iterator<Item = &'a str>,
-    type_definitions: &[TypeDefinition],
-    stop: &str,
-) -> Result<Vec<Field>, Error> {
+    scope: Scope<'_>,
+    stop: Option<&str>,
+    extensible: bool,
+) -> Result<(Vec<Field>, bool), Error> {
     let mut fields = Vec::new();
+    let mut extension = false;
 
     for (idx, part) in def.enumerate() {
-        if part == stop {
+        if Some(part) == stop {
             break;
         }
 
+        let span = line.span(part);
+
+        if extensible && !extension && part == "|" {
+            extension = true;
+            continue;
+        }
+
+        if let Some(name) = part.strip_prefix("..") {
+            let Some(group) = scope.groups.iter().find(|group| group.name == name) else {
+                return Err(Error::UnknownGroup {
+                    span,
+                    name: name.to_owned(),
+                    suggestion: suggest(name, scope.groups.iter().map(|group| group.name.as_str())),
+                });
+            };
+
+            for field in &group.fields {
+                if field_defined(&field.name, &fields) {
+                    return Err(Error::DuplicateField { span, field: field.name.clone() });
+                }
+                if extension && !matches!(field.typ, Type::Option(_)) {
+                    return Err(Error::ExtensionNotOptional { span, field: field.name.clone() });
+                }
+                fields.push(Field {
+                    name: field.name.clone(),
+                    typ: field.typ.clone(),
+                    span,
+                    group: Some(group.name.clone()),
+                    extension,
+                    default: field.default.clone(),
+                });
+            }
+            continue;
+        }
+
         let mut part = part.split(":");
 
         let name = part.next()
-            .ok_or(Error::FieldNameMissing { line, field: idx + 1 })?
-            .to_owned();
-        if field_defined(&name, &fields) {
-            return Err(Error::DuplicateField { line, field: name });
+            .ok_or(Error::FieldNameMissing { span, field: idx + 1 })?;
+        validate_name(line.span(name), name)?;
+        if field_defined(name, &fields) {
+            return Err(Error::DuplicateField { span, field: name.to_owned() });
         }
 
         let typ = part.next()
-            .ok_or(Error::FieldTypeMissing { line, field: name.clone() })?;
-        let typ = parse_type(line, &name, typ, type_definitions, None)?;
+            .ok_or(Error::FieldTypeMissing { span, field: name.to_owned() })?;
+        let (typ, default) = match typ.split_once('=') {
+            Some((typ, default)) => (typ, Some(default)),
+            None => (typ, None),
+        };
+        let typ = parse_type(line, name, typ, scope, None)?;
+        if extension && !matches!(typ, Type::Option(_)) {
+            return Err(Error::ExtensionNotOptional { span, field: name.to_owned() });
+        }
+        if let Some(default) = default {
+            check_default(line.span(default), name, &typ, default, scope)?;
+        }
 
-        fields.push(Field { name, typ });
+        fields.push(Field {
+            name: name.to_owned(),
+            typ,
+            span,
+            group: None,
+            extension,
+            default: default.map(str::to_owned),
+        });
     }
 
-    Ok(fields)
+    Ok((fields, extension))
 }
 
 fn parse_type(
-    line: usize,
+    line: Line<'_>,
     field: &str,
     typ: &str,
-    type_definitions: &[TypeDefinition],
+    scope: Scope<'_>,
     outer: Option<OuterType>,
 ) -> Result<Type, Error> {
+    let text = typ;
     let typ = match typ {
         "int32" => Type::Int32,
         "int64" => Type::Int64,
@@ -237,7 +1278,7 @@ fn parse_type(
                 line,
                 field,
                 &typ[1..typ.len() - 1],
-                type_definitions,
+                scope,
                 Some(OuterType::Vector),
             )?)),
         _ if typ.ends_with('?') =>
@@ -245,30 +1286,149 @@ fn parse_type(
                 line,
                 field,
                 &typ[..typ.len() - 1],
-                type_definitions,
+                scope,
                 Some(OuterType::Option),
             )?)),
-        _ if enum_defined(typ, type_definitions) => Type::Defined(typ.to_owned()),
-        _ => return Err(Error::InvalidType { line, field: field.to_owned(), typ: typ.to_owned() }),
+        _ if enum_defined(typ, scope.types)
+            || record_defined(typ, scope.records)
+            || extern_defined(typ, scope.externs) => {
+            check_feature(line.span(typ), typ, scope)?;
+            Type::Defined(typ.to_owned())
+        }
+        _ => {
+            let enums = scope.types.iter().map(|def| def.r#enum.as_str());
+            let records = scope.records.iter().map(|def| def.core.name.as_str());
+            let externs = scope.externs.iter().map(|def| def.name.as_str());
+            return Err(Error::InvalidType {
+                span: line.span(typ),
+                field: field.to_owned(),
+                typ: typ.to_owned(),
+                suggestion: suggest(typ, BUILTIN_TYPES.into_iter().chain(enums).chain(records).chain(externs)),
+            });
+        }
     };
 
-    if let Some(outer) = outer {
-        if matches!(
-            (&outer, &typ),
-            (OuterType::Vector, Type::Option(_))
-            | (OuterType::Option, Type::Bool)
-            | (OuterType::Option, Type::Vector(_))
-            | (OuterType::Option, Type::Option(_))
-        ) {
+    if let Some(outer) = outer
+        && !can_contain(&outer, &typ)
+    {
+        let span = line.span(text);
+        let span = match outer {
+            OuterType::Vector => Span { start: span.start - 1, end: span.end + 1, ..span },
+            OuterType::Option => Span { end: span.end + 1, ..span },
+        };
+        return Err(Error::InvalidType {
+            span,
+            field: field.to_owned(),
+            typ: format!("{outer:?}<{typ:?}>"),
+            suggestion: None,
+        });
+    }
+
+    Ok(typ)
+}
+
+fn can_contain(outer: &OuterType, typ: &Type) -> bool {
+    !matches!(
+        (outer, typ),
+        (OuterType::Vector, Type::Option(_))
+        | (OuterType::Option, Type::Bool)
+        | (OuterType::Option, Type::Vector(_))
+        | (OuterType::Option, Type::Option(_))
+    )
+}
+
+// The checks of `parse_type` for types built in code, every error points at
+// `span`.
+fn check_type(span: Span, field: &str, typ: &Type, scope: Scope<'_>, outer: Option<OuterType>) -> Result<(), Error> {
+    match typ {
+        Type::Vector(inner) => check_type(span, field, inner, scope, Some(OuterType::Vector))?,
+        Type::Option(inner) => check_type(span, field, inner, scope, Some(OuterType::Option))?,
+        Type::Defined(name) if enum_defined(name, scope.types)
+            || record_defined(name, scope.records)
+            || extern_defined(name, scope.externs) => check_feature(span, name, scope)?,
+        Type::Defined(name) => {
+            let enums = scope.types.iter().map(|def| def.r#enum.as_str());
+            let records = scope.records.iter().map(|def| def.core.name.as_str());
+            let externs = scope.externs.iter().map(|def| def.name.as_str());
             return Err(Error::InvalidType {
-                line,
+                span,
                 field: field.to_owned(),
-                typ: format!("{outer:?}<{typ:?}>"),
+                typ: name.clone(),
+                suggestion: suggest(name, BUILTIN_TYPES.into_iter().chain(enums).chain(records).chain(externs)),
             });
         }
+        Type::Void => return Err(Error::InvalidType {
+            span,
+            field: field.to_owned(),
+            typ: typ.to_string(),
+            suggestion: None,
+        }),
+        _ => {}
     }
 
-    Ok(typ)
+    match outer {
+        Some(outer) if !can_contain(&outer, typ) => Err(Error::InvalidType {
+            span,
+            field: field.to_owned(),
+            typ: format!("{outer:?}<{typ:?}>"),
+            suggestion: None,
+        }),
+        _ => Ok(()),
+    }
+}
+
+// Optional fields take constants of their inner type.
+fn check_default(span: Span, field: &str, typ: &Type, constant: &str, scope: Scope<'_>) -> Result<(), Error> {
+    let Some(found) = scope.constants.iter().find(|def| def.name == constant) else {
+        return Err(Error::UnknownConstant {
+            span,
+            field: field.to_owned(),
+            name: constant.to_owned(),
+            suggestion: suggest(constant, scope.constants.iter().map(|def| def.name.as_str())),
+        });
+    };
+
+    let expected = match typ {
+        Type::Option(inner) => inner,
+        _ => typ,
+    };
+    if found.typ != *expected {
+        return Err(Error::DefaultTypeMismatch {
+            span,
+            field: field.to_owned(),
+            constant: constant.to_owned(),
+            expected: expected.clone(),
+            found: found.typ.clone(),
+        });
+    }
+    Ok(())
+}
+
+fn group_defined(name: &str, definitions: &[GroupDefinition]) -> bool {
+    definitions.iter()
+        .any(|def| def.name == name)
+}
+
+// Gated records only exist with their feature, so only definitions gated by
+// the same feature can refer to them.
+fn check_feature(span: Span, name: &str, scope: Scope<'_>) -> Result<(), Error> {
+    let feature = scope.records.iter()
+        .find(|def| def.core.name == name)
+        .and_then(|def| def.core.feature.as_deref());
+
+    match feature {
+        Some(feature) if scope.feature != Some(feature) => Err(Error::FeatureRequired {
+            span,
+            name: name.to_owned(),
+            feature: feature.to_owned(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn record_defined(name: &str, definitions: &[RecordDefinition]) -> bool {
+    definitions.iter()
+        .any(|def| def.core.name == name)
 }
 
 fn type_defined(name: &str, definitions: &[TypeDefinition]) -> bool {
@@ -291,6 +1451,11 @@ fn field_defined(name: &str, fields: &[Field]) -> bool {
         .any(|f| f.name == name)
 }
 
+fn extern_defined(name: &str, externs: &[Extern]) -> bool {
+    externs.iter()
+        .any(|def| def.name == name)
+}
+
 fn enum_defined(name: &str, type_definitions: &[TypeDefinition]) -> bool {
     type_definitions.iter()
         .any(|def| def.r#enum == name)
@@ -303,6 +1468,487 @@ mod tests {
     #[test]
     fn definition_id() {
         let def = "type Message id:int32 text:string? photos:[bytes] sent_at:time";
-        assert_eq!(compute_definition_id(def), 226668223);
+        assert_eq!(compute_id(def), 226668223);
+    }
+
+    #[test]
+    fn canonical_id() {
+        let schema = parse_schema("\
+# Users.
+type   User id:int64   verified:bool\trating:float = User  # Registered user.
+error InvalidUserId user_id:int64
+func get_users user_ids:[int64] = [User]
+").unwrap();
+
+        let def = &schema.types[0];
+        assert_eq!(def.canonical(), "type User id:int64 verified:bool rating:float = User");
+        assert_eq!(def.core.id, 4055296785);
+        assert_eq!(schema.errors[0].canonical(), "error InvalidUserId user_id:int64");
+        assert_eq!(schema.errors[0].core.id, 2283843567);
+        assert_eq!(schema.functions[0].canonical(), "func get_users user_ids:[int64] = [User]");
+        assert_eq!(schema.functions[0].core.id, 1904452899);
+    }
+
+    #[test]
+    fn canonical_schema() {
+        let schema = parse_schema("\
+error InvalidUserId user_id:int64
+
+type User#0000002a id:int64 = User
+func get_users   user_ids:[int64] = [User]  # Comment.
+").unwrap();
+
+        assert_eq!(schema.canonical(), "\
+type User#0000002a id:int64 = User
+
+error InvalidUserId user_id:int64
+
+func get_users user_ids:[int64] = [User]
+");
+        assert_eq!(parse_schema(&schema.canonical()).unwrap().canonical(), schema.canonical());
+        assert!(parse_schema(META_SCHEMA).is_ok());
+    }
+
+    fn without_spans(mut schema: Schema) -> Schema {
+        for def in &mut schema.externs {
+            def.span = Span::default();
+            def.name_span = Span::default();
+        }
+        for def in &mut schema.groups {
+            def.span = Span::default();
+            def.name_span = Span::default();
+            def.fields.iter_mut().for_each(|field| field.span = Span::default());
+        }
+        let definitions = schema.records.iter_mut().map(|def| &mut def.core)
+            .chain(schema.types.iter_mut().map(|def| &mut def.core))
+            .chain(schema.errors.iter_mut().map(|def| &mut def.core))
+            .chain(schema.functions.iter_mut().map(|def| &mut def.core));
+        for def in definitions {
+            def.span = Span::default();
+            def.name_span = Span::default();
+            def.fields.iter_mut().for_each(|field| field.span = Span::default());
+        }
+        schema.types.iter_mut().for_each(|def| def.enum_span = Span::default());
+        schema.functions.iter_mut().for_each(|def| def.ret_span = Span::default());
+        schema
+    }
+
+    #[test]
+    fn round_trip() {
+        let schema = parse_schema("\
+type Photo data:bytes = Photo
+type  User#0000002a id:int64 photo:Photo? = User   # Registered user.
+reserved OldUser 0x00000001
+
+func get_users user_ids:[int64] = [User]
+error NotFound
+").unwrap();
+
+        let printed = schema.to_string();
+        let reparsed = parse_schema(&printed).unwrap();
+        assert_eq!(reparsed.to_string(), printed);
+        assert_eq!(schema.types[1].to_string(), "type User#0000002a id:int64 photo:Photo? = User");
+        assert_eq!(without_spans(reparsed), without_spans(schema));
+    }
+
+    #[test]
+    fn groups() {
+        let schema = parse_schema("\
+group Timestamps created_at:time updated_at:time
+type User id:int64 ..Timestamps name:string = User
+error NotFound ..Timestamps
+").unwrap();
+
+        let fields = schema.types[0].core.fields.iter()
+            .map(|field| (field.name.as_str(), field.group.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec![
+            ("id", None),
+            ("created_at", Some("Timestamps")),
+            ("updated_at", Some("Timestamps")),
+            ("name", None),
+        ]);
+        assert_eq!(
+            schema.types[0].canonical(),
+            "type User id:int64 created_at:time updated_at:time name:string = User",
+        );
+        assert_eq!(schema.types[0].to_string(), "type User id:int64 ..Timestamps name:string = User");
+
+        let expanded = parse_schema("type User id:int64 created_at:time updated_at:time name:string = User").unwrap();
+        assert_eq!(schema.types[0].core.id, expanded.types[0].core.id);
+        assert_eq!(without_spans(parse_schema(&schema.to_string()).unwrap()), without_spans(schema));
+
+        assert_eq!(
+            parse_schema_recovering("\
+group Timestamps created_at:time
+type User ..Timestamp = User
+type Post created_at:time ..Timestamps = Post
+group Timestamps
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: unknown field group: Timestamp",
+                "line 3: duplicate field: created_at",
+                "line 4: duplicate definition",
+            ],
+        );
+    }
+
+    #[test]
+    fn records() {
+        let err = parse_schema("type Shape points:[Point] = Shape\nrecord Point x:float y:float").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: field points: invalid type: Point");
+
+        let schema = parse_schema("\
+record Point x:float y:float
+group Located position:Point
+record Marker ..Located label:string
+type Shape points:[Point] marker:Marker? = Shape
+").unwrap();
+
+        assert_eq!(schema.records[0].core.id, 0);
+        assert_eq!(schema.records[1].canonical(), "record Marker position:Point label:string");
+        assert_eq!(schema.records[1].to_string(), "record Marker ..Located label:string");
+        assert_eq!(
+            schema.types[0].core.fields[0].typ,
+            Type::Vector(Box::new(Type::Defined("Point".to_owned()))),
+        );
+        assert_eq!(without_spans(parse_schema(&schema.to_string()).unwrap()), without_spans(schema));
+
+        assert_eq!(
+            parse_schema_recovering("\
+type Point = Point
+record Point x:float
+record Size width:float
+record Size height:float
+type Rect = Size
+record Line#01 from:Point
+reserved Size
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: duplicate definition",
+                "line 4: duplicate definition",
+                "line 5: duplicate definition",
+                "line 6: invalid name: Line#01",
+                "line 3: name Size is reserved",
+            ],
+        );
+    }
+
+    #[test]
+    fn extensions() {
+        let schema = parse_schema("\
+group Profile bio:string? website:string?
+type User id:int64 | ..Profile = User
+type UserEmpty id:int64 | = User
+").unwrap();
+
+        let user = &schema.types[0].core;
+        assert!(user.extensible);
+        assert_eq!(
+            user.fields.iter().map(|field| (field.name.as_str(), field.extension)).collect::<Vec<_>>(),
+            vec![("id", false), ("bio", true), ("website", true)],
+        );
+        assert_eq!(schema.types[0].canonical(), "type User id:int64 | = User");
+        assert_eq!(schema.types[0].to_string(), "type User id:int64 | ..Profile = User");
+        assert_eq!(schema.types[1].to_string(), "type UserEmpty id:int64 | = User");
+        assert_eq!(without_spans(parse_schema(&schema.to_string()).unwrap()), without_spans(schema.clone()));
+
+        let appended = parse_schema("type User id:int64 | bio:string? website:string? age:int32? = User").unwrap();
+        assert_eq!(appended.types[0].core.id, user.id);
+        let bare = parse_schema("type User id:int64 | = User").unwrap();
+        assert_eq!(bare.types[0].core.id, user.id);
+        let plain = parse_schema("type User id:int64 = User").unwrap();
+        assert_ne!(plain.types[0].core.id, user.id);
+
+        assert_eq!(
+            parse_schema_recovering("\
+type User id:int64 | age:int32 = User
+type Photo | data:bytes? | = Photo
+group Profile | bio:string?
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 1: field age: extension fields must be optional",
+                "line 2: invalid name: |",
+                "line 3: invalid name: |",
+            ],
+        );
+    }
+
+    #[test]
+    fn externs() {
+        let schema = parse_schema("\
+extern common User Money
+type Order buyer:User total:Money items:[Money] = Order
+").unwrap();
+
+        assert_eq!(
+            schema.externs.iter().map(|def| (def.schema.as_str(), def.name.as_str())).collect::<Vec<_>>(),
+            vec![("common", "User"), ("common", "Money")],
+        );
+        assert_eq!(schema.types[0].core.fields[0].typ, Type::Defined("User".to_owned()));
+        assert_eq!(
+            schema.types[0].core.id,
+            parse_schema("type User = User\ntype Money = Money\ntype Order buyer:User total:Money items:[Money] = Order")
+                .unwrap().types[2].core.id,
+        );
+        assert_eq!(without_spans(parse_schema(&schema.to_string()).unwrap()), without_spans(schema));
+
+        assert_eq!(
+            parse_schema_recovering("\
+extern common User
+extern billing User
+type Account = User
+extern common
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: duplicate definition",
+                "line 3: duplicate definition",
+                "line 4: definition name is missing",
+            ],
+        );
+    }
+
+    #[test]
+    fn constants() {
+        let schema = parse_schema("\
+const MAX_TEXT_LENGTH:int32 = 4096
+const MIN_RATING:float = -0.5
+const GREETING:string = \"Hello,  #1 world!\"  # Shown to new users.
+error TooLongText text:string max_length:int32=MAX_TEXT_LENGTH
+func get_rating min:float?=MIN_RATING = float
+").unwrap();
+
+        assert_eq!(schema.constants[0].value, Value::Int(4096));
+        assert_eq!(schema.constants[1].value, Value::Float(-0.5));
+        assert_eq!(schema.constants[2].to_string(), "const GREETING:string = \"Hello,  #1 world!\"");
+        assert_eq!(schema.errors[0].core.fields[1].default.as_deref(), Some("MAX_TEXT_LENGTH"));
+        assert_eq!(schema.errors[0].to_string(), "error TooLongText text:string max_length:int32=MAX_TEXT_LENGTH");
+        assert_eq!(schema.errors[0].canonical(), "error TooLongText text:string max_length:int32");
+        assert_eq!(parse_schema(&schema.to_string()).unwrap().to_string(), schema.to_string());
+
+        assert_eq!(
+            parse_schema_recovering("\
+const LIMIT:int32 = 3000000000
+const LIMIT:int64 = 3000000000
+const LIMIT:int64 = 1
+const RATIO:flaot = 0.5
+const NAME:string = unquoted
+const ENABLED:bool
+const TIMEOUT = 30
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 1: constant LIMIT: invalid int32 value: 3000000000",
+                "line 3: duplicate definition",
+                "line 4: constant RATIO: invalid type: flaot",
+                "line 5: constant NAME: invalid string value: unquoted",
+                "line 6: constant ENABLED: value is missing",
+                "line 7: constant TIMEOUT: type is missing",
+            ],
+        );
+
+        assert_eq!(
+            parse_schema_recovering("\
+const MAX_TEXT_LENGTH:int32 = 4096
+error TooLongText max_length:int32=MAX_TEXT_LENTGH
+error TooLongName max_length:int64=MAX_TEXT_LENGTH
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: field max_length: unknown constant: MAX_TEXT_LENTGH",
+                "line 3: field max_length: constant MAX_TEXT_LENGTH is int32, not int64",
+            ],
+        );
+    }
+
+    #[test]
+    fn void() {
+        let schema = parse_schema("func log_out = void\n").unwrap();
+
+        assert_eq!(schema.functions[0].ret, Type::Void);
+        assert_eq!(schema.functions[0].to_string(), "func log_out = void");
+        assert_eq!(parse_schema(&schema.to_string()).unwrap().functions, schema.functions);
+
+        assert_eq!(
+            parse_schema_recovering("\
+type User status:void = User
+func get_users = [void]
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 1: field status: invalid type: void",
+                "line 2: field <return>: invalid type: void",
+            ],
+        );
+    }
+
+    #[test]
+    fn function_errors() {
+        let schema = parse_schema("\
+error NotFound
+error InvalidUserId user_id:int64
+func get_user id:int64 = int64 ! NotFound InvalidUserId
+").unwrap();
+
+        let def = &schema.functions[0];
+        assert_eq!(def.errors, vec![
+            ("NotFound".to_owned(), Span { line: 3, start: 33, end: 41 }),
+            ("InvalidUserId".to_owned(), Span { line: 3, start: 42, end: 55 }),
+        ]);
+        assert_eq!(def.to_string(), "func get_user id:int64 = int64 ! NotFound InvalidUserId");
+        assert_eq!(def.canonical(), "func get_user id:int64 = int64");
+
+        assert_eq!(
+            parse_schema_recovering("\
+error NotFound
+func get_user = int64 ! NotFoud
+func get_users = [int64] !
+func get_admins = [int64] ! NotFound NotFound
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: unknown error: NotFoud",
+                "line 3: errors are missing after `!`",
+                "line 4: error NotFound is listed twice",
+            ],
+        );
+    }
+
+    #[test]
+    fn features() {
+        let schema = parse_schema("\
+@feature(\"admin\") record Ban reason:string until:time
+type User id:int64 = User
+@feature(\"admin\") type BannedUser id:int64 ban:Ban = User
+@feature(\"admin\") func ban_user id:int64 ban:Ban = void
+").unwrap();
+
+        assert_eq!(schema.records[0].core.feature.as_deref(), Some("admin"));
+        assert_eq!(schema.types[0].core.feature, None);
+        assert_eq!(schema.functions[0].to_string(), "@feature(\"admin\") func ban_user id:int64 ban:Ban = void");
+        assert_eq!(schema.functions[0].canonical(), "func ban_user id:int64 ban:Ban = void");
+        assert_eq!(parse_schema(&schema.to_string()).unwrap().to_string(), schema.to_string());
+
+        assert_eq!(
+            parse_schema_recovering("\
+@feature(\"admin\") record Ban reason:string
+@feature(\"internal\") func ban_user ban:Ban = void
+func unban_user ban:Ban? = void
+@feature(admin) func get_bans = [Ban]
+@feature(\"admin\") group Banned ban:Ban
+@feature(\"admin\")
+").1.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: Ban requires feature admin",
+                "line 3: Ban requires feature admin",
+                "line 4: invalid annotation: @feature(admin)",
+                "line 5: only records, types, errors and functions can be annotated",
+                "line 6: only records, types, errors and functions can be annotated",
+            ],
+        );
+    }
+
+    #[test]
+    fn reserved() {
+        let (schema, errors) = parse_schema_recovering("\
+reserved OldUser 0x0000002a
+type User#0000002a id:int64 = User
+type OldUser = User
+");
+
+        assert_eq!(schema.reserved, vec![Reserved::Name("OldUser".to_owned()), Reserved::Id(0x2a)]);
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: User has reserved id 0000002a",
+                "line 3: name OldUser is reserved",
+            ],
+        );
+        assert!(schema.canonical().ends_with("\nreserved OldUser\nreserved 0x0000002a\n"));
+    }
+
+    #[test]
+    fn explicit_id() {
+        let schema = parse_schema("type User#00c0ffee id:int64 = User").unwrap();
+        assert_eq!(schema.types[0].core.name, "User");
+        assert_eq!(schema.types[0].core.id, 0xc0ffee);
+
+        assert!(matches!(
+            parse_schema("type User#c0ffeeee1 id:int64 = User"),
+            Err(Error::InvalidDefinitionId { span: Span { line: 1, start: 10, end: 19 }, .. }),
+        ));
+    }
+
+    #[test]
+    fn names() {
+        assert!(parse_schema("type User_2 _id:int64 r#type:int32 = User").is_err());
+        assert!(parse_schema("type User_2 _id:int64 type:int32 = User").is_ok());
+
+        for schema in [
+            "type 2User = User",
+            "type User-Empty = User",
+            "type User = User»",
+            "type User _:int64 = User",
+            "error NotFound user-id:int64",
+            "func get:user = [int64]",
+        ] {
+            assert!(matches!(parse_schema(schema), Err(Error::InvalidName { span: Span { line: 1, .. }, .. })), "{schema}");
+        }
+    }
+
+    #[test]
+    fn id_collisions() {
+        let schema = "\
+type A#00000001 = X
+type B#00000002 = X
+type C#00000001 = X
+type D#00000002 = Y
+error E#00000002
+func f#00000002 = X
+func g#00000002 = X
+";
+
+        let (_, errors) = parse_schema_recovering(schema);
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 3: C has id 00000001 which collides with A on line 1, set an explicit id like C#00000003",
+                "line 7: g has id 00000002 which collides with f on line 6, set an explicit id like g#00000003",
+            ],
+        );
+
+        let options = Options { schema_wide_id_check: true };
+        let (_, errors) = parse_schema_with_options(schema, &options);
+        assert_eq!(
+            errors.iter()
+                .map(|err| match err {
+                    Error::IdCollision { span, other_line, .. } => (span.line, *other_line),
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>(),
+            vec![(3, 1), (4, 2), (5, 2), (6, 2), (7, 2)],
+        );
+    }
+
+    #[test]
+    fn recovery() {
+        let (schema, errors) = parse_schema_recovering("\
+type User id:int64 = User
+type Broken id:nope = User
+error NotFound
+errror Typo
+func get_user id:int64 = User
+func get_user id:int64 = User
+func get_photo = Photo
+");
+
+        assert_eq!(schema.types.len(), 1);
+        assert_eq!(schema.errors.len(), 1);
+        assert_eq!(schema.functions.len(), 1);
+        assert_eq!(
+            errors.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 2: field id: invalid type: nope",
+                "line 4: invalid definition type",
+                "line 6: duplicate definition",
+                "line 7: field <return>: invalid type: Photo",
+            ],
+        );
     }
 }
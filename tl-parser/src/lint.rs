@@ -0,0 +1,311 @@
+use crate::graph::Graph;
+use crate::{DefinitionCore, Schema};
+use std::collections::HashSet;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lint {
+    FieldNaming,
+    DefinitionNaming,
+    SingleConstructorEnum,
+    UnusedType,
+    // Errors no function lists after `!`. Listing errors is optional, so it
+    // only warns once some function in the schema lists any.
+    UnusedError,
+    LargeDefinition,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::FieldNaming,
+        Lint::DefinitionNaming,
+        Lint::SingleConstructorEnum,
+        Lint::UnusedType,
+        Lint::UnusedError,
+        Lint::LargeDefinition,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Lint> {
+        Lint::ALL.into_iter().find(|lint| lint.name() == name)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Lint::FieldNaming => "field-naming",
+            Lint::DefinitionNaming => "definition-naming",
+            Lint::SingleConstructorEnum => "single-constructor-enum",
+            Lint::UnusedType => "unused-type",
+            Lint::UnusedError => "unused-error",
+            Lint::LargeDefinition => "large-definition",
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    disabled: HashSet<Lint>,
+    pub max_fields: usize,
+}
+
+impl Config {
+    pub fn enable(&mut self, lint: Lint) -> &mut Self {
+        self.disabled.remove(&lint);
+        self
+    }
+
+    pub fn disable(&mut self, lint: Lint) -> &mut Self {
+        self.disabled.insert(lint);
+        self
+    }
+
+    pub fn is_enabled(&self, lint: Lint) -> bool {
+        !self.disabled.contains(&lint)
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            disabled: HashSet::new(),
+            max_fields: 32,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    pub lint: Lint,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {} [{}]", self.line, self.message, self.lint)
+    }
+}
+
+pub fn lint_schema(schema: &Schema, config: &Config) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    let mut warn = |lint: Lint, line: usize, message: String| {
+        if config.is_enabled(lint) {
+            warnings.push(Warning { lint, line, message });
+        }
+    };
+
+    let definitions = schema.records.iter().map(|def| &def.core)
+        .chain(schema.types.iter().map(|def| &def.core))
+        .chain(schema.errors.iter().map(|def| &def.core))
+        .chain(schema.functions.iter().map(|def| &def.core));
+
+    for def in definitions {
+        for field in &def.fields {
+            if !is_snake_case(&field.name) {
+                warn(Lint::FieldNaming, def.span.line, format!("field {} of {} should be snake_case", field.name, def.name));
+            }
+        }
+
+        if def.fields.len() > config.max_fields {
+            warn(
+                Lint::LargeDefinition,
+                def.span.line,
+                format!("{} has {} fields, more than {}", def.name, def.fields.len(), config.max_fields),
+            );
+        }
+    }
+
+    for def in schema.types.iter().map(|def| &def.core).chain(schema.errors.iter().map(|def| &def.core)) {
+        if !is_pascal_case(&def.name) {
+            warn(Lint::DefinitionNaming, def.span.line, format!("constructor {} should be PascalCase", def.name));
+        }
+    }
+
+    for constant in &schema.constants {
+        if !is_screaming_snake_case(&constant.name) {
+            let message = format!("constant {} should be SCREAMING_SNAKE_CASE", constant.name);
+            warn(Lint::DefinitionNaming, constant.span.line, message);
+        }
+    }
+
+    for def in &schema.records {
+        if !is_pascal_case(&def.core.name) {
+            warn(Lint::DefinitionNaming, def.core.span.line, format!("record {} should be PascalCase", def.core.name));
+        }
+    }
+
+    for def in &schema.functions {
+        if !is_snake_case(&def.core.name) {
+            warn(Lint::DefinitionNaming, def.core.span.line, format!("function {} should be snake_case", def.core.name));
+        }
+    }
+
+    let graph = Graph::new(schema);
+    let unused = graph.unused_types();
+
+    for def in &schema.records {
+        if unused.contains(&def.core.name.as_str()) {
+            let message = format!("record {} is not used by any function or error", def.core.name);
+            warn(Lint::UnusedType, def.core.span.line, message);
+        }
+    }
+
+    let unused_errors = graph.unused_errors();
+    for def in &schema.errors {
+        if unused_errors.contains(&def.core.name.as_str()) {
+            let message = format!("error {} is not listed by any function", def.core.name);
+            warn(Lint::UnusedError, def.core.span.line, message);
+        }
+    }
+
+    for (name, constructors) in enums(schema) {
+        let line = constructors[0].span.line;
+
+        if !is_pascal_case(name) {
+            warn(Lint::DefinitionNaming, line, format!("enum {name} should be PascalCase"));
+        }
+
+        if let [constructor] = constructors.as_slice() {
+            warn(
+                Lint::SingleConstructorEnum,
+                line,
+                format!("enum {name} has a single constructor {}", constructor.name),
+            );
+        }
+
+        if unused.contains(&name) {
+            warn(Lint::UnusedType, line, format!("enum {name} is not used by any function or error"));
+        }
+    }
+
+    warnings.sort_by_key(|warning| warning.line);
+    warnings
+}
+
+fn enums(schema: &Schema) -> Vec<(&str, Vec<&DefinitionCore>)> {
+    let mut enums: Vec<(&str, Vec<&DefinitionCore>)> = Vec::new();
+    for def in &schema.types {
+        match enums.iter_mut().find(|(name, _)| *name == def.r#enum) {
+            Some((_, constructors)) => constructors.push(&def.core),
+            None => enums.push((&def.r#enum, vec![&def.core])),
+        }
+    }
+    enums
+}
+
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+        && !name.ends_with('_')
+        && !name.contains("__")
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn is_screaming_snake_case(name: &str) -> bool {
+    is_snake_case(&name.to_ascii_lowercase())
+        && name.chars().all(|c| !c.is_ascii_lowercase())
+}
+
+fn is_pascal_case(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_schema;
+
+    #[test]
+    fn warnings() {
+        let schema = parse_schema("\
+type userProfile Id:int64 = User
+type Photo data:bytes = Photo
+type Point x:float y:float = Point
+type Unused = Orphan
+type OrphanToo = Orphan
+record Size width:float height:float
+record size_2d width:float
+
+error NotFound what:Point size:Size
+
+func GetUser id:int64 = User
+const maxUsers:int32 = 100
+").unwrap();
+
+        let warnings = lint_schema(&schema, &Config::default())
+            .into_iter()
+            .map(|warning| (warning.lint, warning.line))
+            .collect::<Vec<_>>();
+
+        assert_eq!(warnings, vec![
+            (Lint::FieldNaming, 1),
+            (Lint::DefinitionNaming, 1),
+            (Lint::SingleConstructorEnum, 1),
+            (Lint::SingleConstructorEnum, 2),
+            (Lint::UnusedType, 2),
+            (Lint::SingleConstructorEnum, 3),
+            (Lint::UnusedType, 4),
+            (Lint::DefinitionNaming, 7),
+            (Lint::UnusedType, 7),
+            (Lint::DefinitionNaming, 11),
+            (Lint::DefinitionNaming, 12),
+        ]);
+    }
+
+    #[test]
+    fn config() {
+        let schema = parse_schema("type Point x:float y:float z:float = Point\nfunc get_point = Point").unwrap();
+
+        let mut config = Config::default();
+        config.disable(Lint::SingleConstructorEnum);
+        assert_eq!(lint_schema(&schema, &config), vec![]);
+
+        config.max_fields = 2;
+        assert_eq!(lint_schema(&schema, &config), vec![Warning {
+            lint: Lint::LargeDefinition,
+            line: 1,
+            message: "Point has 3 fields, more than 2".to_owned(),
+        }]);
+    }
+
+    #[test]
+    fn unused_errors() {
+        let schema = parse_schema("\
+type User id:int64 = User
+error NotFound
+error InvalidUserId user_id:int64
+error RateLimited
+func get_user id:int64 = User ! NotFound
+func get_users ids:[int64] = [User] ! NotFound InvalidUserId
+").unwrap();
+
+        let mut config = Config::default();
+        config.disable(Lint::SingleConstructorEnum);
+        assert_eq!(lint_schema(&schema, &config), vec![Warning {
+            lint: Lint::UnusedError,
+            line: 4,
+            message: "error RateLimited is not listed by any function".to_owned(),
+        }]);
+
+        assert_eq!(Lint::from_name("unused-error"), Some(Lint::UnusedError));
+        assert_eq!(Lint::from_name("unused"), None);
+    }
+
+    #[test]
+    fn unused_errors_without_lists() {
+        let schema = parse_schema("\
+type User id:int64 = User
+error NotFound
+error RateLimited
+func get_user id:int64 = User
+").unwrap();
+
+        let warnings = lint_schema(&schema, &Config::default());
+        assert!(warnings.iter().all(|warning| warning.lint != Lint::UnusedError), "{warnings:?}");
+    }
+}
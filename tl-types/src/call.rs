@@ -1,5 +1,17 @@
 use crate::{Deserialize, Serialize};
 
+// `Return` is `Serialize` on the server and `Deserialize` on the client,
+// generated code only has the impls its role needs.
 pub trait Call {
-    type Return: Serialize + Deserialize;
+    type Return;
 }
+
+// A function the client sends and reads the result of.
+pub trait ClientCall: Call<Return: Deserialize> + Serialize {}
+
+impl<T: Call<Return: Deserialize> + Serialize> ClientCall for T {}
+
+// A function the server reads and writes the result of.
+pub trait ServerCall: Call<Return: Serialize> + Deserialize {}
+
+impl<T: Call<Return: Serialize> + Deserialize> ServerCall for T {}
@@ -56,6 +56,12 @@ impl Deserialize for f64 {
     }
 }
 
+impl Deserialize for () {
+    fn deserialize(_src: &mut &[u8]) -> Result<Self, Error> {
+        Ok(())
+    }
+}
+
 impl Deserialize for bool {
     fn deserialize(src: &mut &[u8]) -> Result<Self, Error> {
         let byte = u8::deserialize(src)?;
@@ -117,6 +123,15 @@ impl<T: Deserialize, E: Deserialize> Deserialize for Result<T, E> {
     }
 }
 
+// Reads a field from an extension block, an older peer may have written
+// fewer extension fields, the missing ones default.
+pub fn extension<T: Deserialize + Default>(src: &mut &[u8]) -> Result<T, Error> {
+    if src.is_empty() {
+        return Ok(T::default());
+    }
+    T::deserialize(src)
+}
+
 fn deserialize_dyn_len(src: &mut &[u8]) -> Result<usize, Error> {
     let byte = u8::deserialize(src)?;
     if byte < 255 {
@@ -139,6 +154,7 @@ mod tests {
         assert_eq!(f64::from_bytes(&[0xbc, 0x90, 0x0e, 0x0f, 0x61, 0x3a, 0x81, 0x40])?, 551.297392_f64);
         assert_eq!(bool::from_bytes(&[0x1])?, true);
         assert_eq!(bool::from_bytes(&[0x0])?, false);
+        <()>::from_bytes(&[])?;
         assert_eq!(String::from_bytes(&[0x5, b'h', b'e', b'l', b'l', b'o'])?, String::from("hello"));
         assert_eq!(
             Vec::<u8>::from_bytes(&[vec![0xff, 0xe8, 0x3, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0], vec![0xdd; 997], vec![b'j', b'o', b'y']].concat())?,
@@ -149,6 +165,14 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn extensions() -> Result<(), Error> {
+        let mut src = &[0x1, 0x28, 0x0, 0x0, 0x0][..];
+        assert_eq!(extension::<Option<i32>>(&mut src)?, Some(0x28));
+        assert_eq!(extension::<Option<i32>>(&mut src)?, None);
+        Ok(())
+    }
+
     #[test]
     fn dyn_len() -> Result<(), Error> {
         assert_eq!(dyn_len_from_bytes(vec![0x50])?, 0x50);
@@ -4,8 +4,11 @@ mod serialize;
 pub mod deserialize;
 mod call;
 mod read;
+// Checked in generated code, rustfmt would make it differ from the generator's output.
+#[rustfmt::skip]
+pub mod meta;
 
-pub use call::Call;
+pub use call::{Call, ClientCall, ServerCall};
 pub use deserialize::Deserialize;
 use read::Read;
 pub use serialize::Serialize;
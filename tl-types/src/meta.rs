@@ -0,0 +1,300 @@
+// Generated from tl-parser's meta.tl, the `meta` test of tl-generator checks it's up to date.
+// Rerun it with `TL_UPDATE_SNAPSHOTS=1` after changing the meta-schema.
+
+pub const SCHEMA: &str = "type Field name:string typ:string extension:bool = Field\ntype RecordDefinition name:string fields:[Field] = Definition\ntype TypeDefinition id:int64 name:string fields:[Field] enum_name:string = Definition\ntype ErrorDefinition id:int64 name:string fields:[Field] = Definition\ntype FunctionDefinition id:int64 name:string fields:[Field] ret:string = Definition\ntype Schema fingerprint:bytes definitions:[Definition] = Schema\n";
+pub const SCHEMA_FINGERPRINT: [u8; 32] = [
+    147, 99, 159, 71, 18, 220, 184, 155, 235, 108, 91, 46, 47, 237, 109, 127, 56, 139,
+    64, 242, 185, 248, 83, 14, 238, 135, 99, 250, 236, 233, 180, 178,
+];
+pub const SCHEMA_DESCRIPTION: &[u8] = &[
+    134, 243, 27, 150, 32, 147, 99, 159, 71, 18, 220, 184, 155, 235, 108, 91, 46, 47,
+    237, 109, 127, 56, 139, 64, 242, 185, 248, 83, 14, 238, 135, 99, 250, 236, 233, 180,
+    178, 6, 255, 166, 119, 76, 28, 176, 45, 178, 0, 0, 0, 0, 5, 70, 105, 101, 108, 100,
+    3, 28, 176, 45, 178, 4, 110, 97, 109, 101, 6, 115, 116, 114, 105, 110, 103, 0, 28,
+    176, 45, 178, 3, 116, 121, 112, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178,
+    9, 101, 120, 116, 101, 110, 115, 105, 111, 110, 4, 98, 111, 111, 108, 0, 5, 70, 105,
+    101, 108, 100, 255, 166, 119, 76, 47, 113, 242, 200, 0, 0, 0, 0, 16, 82, 101, 99,
+    111, 114, 100, 68, 101, 102, 105, 110, 105, 116, 105, 111, 110, 2, 28, 176, 45, 178,
+    4, 110, 97, 109, 101, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178, 6, 102,
+    105, 101, 108, 100, 115, 7, 91, 70, 105, 101, 108, 100, 93, 0, 10, 68, 101, 102, 105,
+    110, 105, 116, 105, 111, 110, 255, 166, 119, 76, 255, 166, 119, 76, 0, 0, 0, 0, 14,
+    84, 121, 112, 101, 68, 101, 102, 105, 110, 105, 116, 105, 111, 110, 4, 28, 176, 45,
+    178, 2, 105, 100, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 4, 110, 97, 109,
+    101, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178, 6, 102, 105, 101, 108,
+    100, 115, 7, 91, 70, 105, 101, 108, 100, 93, 0, 28, 176, 45, 178, 9, 101, 110, 117,
+    109, 95, 110, 97, 109, 101, 6, 115, 116, 114, 105, 110, 103, 0, 10, 68, 101, 102,
+    105, 110, 105, 116, 105, 111, 110, 255, 166, 119, 76, 123, 219, 189, 67, 0, 0, 0, 0,
+    15, 69, 114, 114, 111, 114, 68, 101, 102, 105, 110, 105, 116, 105, 111, 110, 3, 28,
+    176, 45, 178, 2, 105, 100, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 4, 110, 97,
+    109, 101, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178, 6, 102, 105, 101,
+    108, 100, 115, 7, 91, 70, 105, 101, 108, 100, 93, 0, 10, 68, 101, 102, 105, 110, 105,
+    116, 105, 111, 110, 255, 166, 119, 76, 206, 170, 42, 19, 0, 0, 0, 0, 18, 70, 117,
+    110, 99, 116, 105, 111, 110, 68, 101, 102, 105, 110, 105, 116, 105, 111, 110, 4, 28,
+    176, 45, 178, 2, 105, 100, 5, 105, 110, 116, 54, 52, 0, 28, 176, 45, 178, 4, 110, 97,
+    109, 101, 6, 115, 116, 114, 105, 110, 103, 0, 28, 176, 45, 178, 6, 102, 105, 101,
+    108, 100, 115, 7, 91, 70, 105, 101, 108, 100, 93, 0, 28, 176, 45, 178, 3, 114, 101,
+    116, 6, 115, 116, 114, 105, 110, 103, 0, 10, 68, 101, 102, 105, 110, 105, 116, 105,
+    111, 110, 255, 166, 119, 76, 134, 243, 27, 150, 0, 0, 0, 0, 6, 83, 99, 104, 101, 109,
+    97, 2, 28, 176, 45, 178, 11, 102, 105, 110, 103, 101, 114, 112, 114, 105, 110, 116,
+    5, 98, 121, 116, 101, 115, 0, 28, 176, 45, 178, 11, 100, 101, 102, 105, 110, 105,
+    116, 105, 111, 110, 115, 12, 91, 68, 101, 102, 105, 110, 105, 116, 105, 111, 110, 93,
+    0, 6, 83, 99, 104, 101, 109, 97,
+];
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {}
+impl crate::Serialize for Error {
+    fn serialize(&self, _dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+        match *self {}
+    }
+}
+impl crate::Deserialize for Error {
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Err(
+            crate::deserialize::Error::UnexpectedDefinitionId(id),
+        )
+    }
+}
+#[derive(Debug, Clone, PartialEq)]
+pub enum Function {}
+impl crate::Deserialize for Function {
+    fn deserialize(
+        src: &mut &[::core::primitive::u8],
+    ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+        let id = ::core::primitive::u32::deserialize(src)?;
+        ::core::result::Result::Err(
+            crate::deserialize::Error::UnexpectedDefinitionId(id),
+        )
+    }
+}
+pub mod types {
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Field {
+        Field {
+            name: ::std::string::String,
+            typ: ::std::string::String,
+            extension: ::core::primitive::bool,
+        },
+    }
+    impl crate::Serialize for Field {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Field { name: name_, typ: typ_, extension: extension_ } => {
+                    2989338652u32.serialize(dst);
+                    name_.serialize(dst);
+                    typ_.serialize(dst);
+                    extension_.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Field {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    2989338652u32 => {
+                        let name_ = ::std::string::String::deserialize(src)?;
+                        let typ_ = ::std::string::String::deserialize(src)?;
+                        let extension_ = ::core::primitive::bool::deserialize(src)?;
+                        Self::Field {
+                            name: name_,
+                            typ: typ_,
+                            extension: extension_,
+                        }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Definition {
+        RecordDefinition {
+            name: ::std::string::String,
+            fields: ::std::vec::Vec<super::types::Field>,
+        },
+        TypeDefinition {
+            id: ::core::primitive::i64,
+            name: ::std::string::String,
+            fields: ::std::vec::Vec<super::types::Field>,
+            enum_name: ::std::string::String,
+        },
+        ErrorDefinition {
+            id: ::core::primitive::i64,
+            name: ::std::string::String,
+            fields: ::std::vec::Vec<super::types::Field>,
+        },
+        FunctionDefinition {
+            id: ::core::primitive::i64,
+            name: ::std::string::String,
+            fields: ::std::vec::Vec<super::types::Field>,
+            ret: ::std::string::String,
+        },
+    }
+    impl crate::Serialize for Definition {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::RecordDefinition { name: name_, fields: fields_ } => {
+                    3371331887u32.serialize(dst);
+                    name_.serialize(dst);
+                    fields_.serialize(dst);
+                }
+                Self::TypeDefinition {
+                    id: id_,
+                    name: name_,
+                    fields: fields_,
+                    enum_name: enum_name_,
+                } => {
+                    1282909951u32.serialize(dst);
+                    id_.serialize(dst);
+                    name_.serialize(dst);
+                    fields_.serialize(dst);
+                    enum_name_.serialize(dst);
+                }
+                Self::ErrorDefinition { id: id_, name: name_, fields: fields_ } => {
+                    1136515963u32.serialize(dst);
+                    id_.serialize(dst);
+                    name_.serialize(dst);
+                    fields_.serialize(dst);
+                }
+                Self::FunctionDefinition {
+                    id: id_,
+                    name: name_,
+                    fields: fields_,
+                    ret: ret_,
+                } => {
+                    321563342u32.serialize(dst);
+                    id_.serialize(dst);
+                    name_.serialize(dst);
+                    fields_.serialize(dst);
+                    ret_.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Definition {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    3371331887u32 => {
+                        let name_ = ::std::string::String::deserialize(src)?;
+                        let fields_ = ::std::vec::Vec::<
+                            super::types::Field,
+                        >::deserialize(src)?;
+                        Self::RecordDefinition {
+                            name: name_,
+                            fields: fields_,
+                        }
+                    }
+                    1282909951u32 => {
+                        let id_ = ::core::primitive::i64::deserialize(src)?;
+                        let name_ = ::std::string::String::deserialize(src)?;
+                        let fields_ = ::std::vec::Vec::<
+                            super::types::Field,
+                        >::deserialize(src)?;
+                        let enum_name_ = ::std::string::String::deserialize(src)?;
+                        Self::TypeDefinition {
+                            id: id_,
+                            name: name_,
+                            fields: fields_,
+                            enum_name: enum_name_,
+                        }
+                    }
+                    1136515963u32 => {
+                        let id_ = ::core::primitive::i64::deserialize(src)?;
+                        let name_ = ::std::string::String::deserialize(src)?;
+                        let fields_ = ::std::vec::Vec::<
+                            super::types::Field,
+                        >::deserialize(src)?;
+                        Self::ErrorDefinition {
+                            id: id_,
+                            name: name_,
+                            fields: fields_,
+                        }
+                    }
+                    321563342u32 => {
+                        let id_ = ::core::primitive::i64::deserialize(src)?;
+                        let name_ = ::std::string::String::deserialize(src)?;
+                        let fields_ = ::std::vec::Vec::<
+                            super::types::Field,
+                        >::deserialize(src)?;
+                        let ret_ = ::std::string::String::deserialize(src)?;
+                        Self::FunctionDefinition {
+                            id: id_,
+                            name: name_,
+                            fields: fields_,
+                            ret: ret_,
+                        }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Schema {
+        Schema {
+            fingerprint: ::std::vec::Vec<::core::primitive::u8>,
+            definitions: ::std::vec::Vec<super::types::Definition>,
+        },
+    }
+    impl crate::Serialize for Schema {
+        fn serialize(&self, dst: &mut ::std::vec::Vec<::core::primitive::u8>) {
+            match self {
+                Self::Schema {
+                    fingerprint: fingerprint_,
+                    definitions: definitions_,
+                } => {
+                    2518414214u32.serialize(dst);
+                    fingerprint_.serialize(dst);
+                    definitions_.serialize(dst);
+                }
+            };
+        }
+    }
+    impl crate::Deserialize for Schema {
+        fn deserialize(
+            src: &mut &[::core::primitive::u8],
+        ) -> ::core::result::Result<Self, crate::deserialize::Error> {
+            let id = ::core::primitive::u32::deserialize(src)?;
+            ::core::result::Result::Ok(
+                match id {
+                    2518414214u32 => {
+                        let fingerprint_ = ::std::vec::Vec::<
+                            ::core::primitive::u8,
+                        >::deserialize(src)?;
+                        let definitions_ = ::std::vec::Vec::<
+                            super::types::Definition,
+                        >::deserialize(src)?;
+                        Self::Schema {
+                            fingerprint: fingerprint_,
+                            definitions: definitions_,
+                        }
+                    }
+                    _ => {
+                        return ::core::result::Result::Err(
+                            crate::deserialize::Error::UnexpectedDefinitionId(id),
+                        );
+                    }
+                },
+            )
+        }
+    }
+}
+pub mod functions {}
@@ -40,6 +40,10 @@ impl Serialize for f64 {
     }
 }
 
+impl Serialize for () {
+    fn serialize(&self, _dst: &mut Vec<u8>) {}
+}
+
 impl Serialize for bool {
     fn serialize(&self, dst: &mut Vec<u8>) {
         dst.push(if *self { 1 } else { 0 });
@@ -137,6 +141,7 @@ mod tests {
         assert_eq!(551.297392_f64.to_bytes(), vec![0xbc, 0x90, 0x0e, 0x0f, 0x61, 0x3a, 0x81, 0x40]);
         assert_eq!(true.to_bytes(), vec![0x1]);
         assert_eq!(false.to_bytes(), vec![0x0]);
+        assert_eq!(().to_bytes(), vec![]);
         assert_eq!("hello".to_bytes(), vec![0x5, b'h', b'e', b'l', b'l', b'o']);
         assert_eq!(
             [vec![0xdd; 997], vec![b'j', b'o', b'y']].concat().to_bytes(),